@@ -3,5 +3,12 @@
 npm install
 make init
 make init-nixos
+
+# check the generator against the real UCD, which the mini fixtures only
+# sample, fetched into data/ucd so that the checked-in files stay as they are
+export UCD_DIR="$PWD/data/ucd"
+( cd data; ./update.sh "$UCD_DIR" ) || exit 1
+( cd data; cargo test ) || exit 1
+
 make assets
 npm run build
@@ -0,0 +1,22 @@
+use color_eyre::eyre;
+use regex::Captures;
+
+use crate::captures::CapturesExt;
+
+// (radical, simplified, CJK radical point, CJK unified ideograph point)
+pub(crate) type Radical = (usize, usize, Option<usize>, usize);
+
+pub(crate) fn cjkr_handler(sink: &mut Vec<Radical>, captures: Captures) -> eyre::Result<()> {
+    let radical = captures.try_name("radical")?;
+    let simplified = radical.chars().filter(|&x| x == '\'').count();
+    let radical = radical.trim_end_matches('\'').parse()?;
+    let character = captures
+        .name("character")
+        .map(|x| usize::from_str_radix(x.as_str(), 16))
+        .transpose()?;
+    let ideograph = usize::from_str_radix(captures.try_name("ideograph")?, 16)?;
+
+    sink.push((radical, simplified, character, ideograph));
+
+    Ok(())
+}
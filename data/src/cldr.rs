@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use color_eyre::eyre;
+use regex::Captures;
+
+use crate::captures::CapturesExt;
+use crate::details::{Alias, AliasType, Details};
+use crate::pool::Popularity;
+use crate::sequence::Sequences;
+
+pub(crate) fn cldr_name_handler(
+    sink: &mut BTreeMap<String, String>,
+    captures: Captures,
+) -> eyre::Result<()> {
+    let code = captures.try_name("code")?;
+    let name = captures.try_name("name")?;
+
+    sink.insert(code.to_owned(), unescape(name));
+
+    Ok(())
+}
+
+pub(crate) fn annotation_handler(
+    popularity: &mut Popularity,
+    sink: &mut [Details],
+    sequences: &mut Sequences,
+    captures: Captures,
+) -> eyre::Result<()> {
+    let cp = unescape(captures.try_name("cp")?);
+    let keywords = unescape(captures.try_name("keywords")?);
+    let points = cp.chars().map(|x| x as usize).collect::<Vec<_>>();
+
+    let points = qualified_points(sink, sequences, points);
+
+    // sequences are only annotated if another input defines them
+    let (alias, names) = if points.len() > 1 {
+        let Some(sequence) = sequences.get_mut(&points) else {
+            return Ok(());
+        };
+        (&mut sequence.alias, &sequence.names[..])
+    } else {
+        (&mut sink[points[0]].alias, &[][..])
+    };
+    for keyword in keywords.split('|').map(str::trim) {
+        if alias.iter().any(|x| &*x.inner == keyword) || names.iter().any(|x| &**x == keyword) {
+            continue;
+        }
+        alias.push(Alias {
+            inner: popularity.vote(keyword),
+            r#type: AliasType::CldrKeyword,
+        });
+    }
+
+    Ok(())
+}
+
+// Annotations are keyed by emoji without any emoji presentation
+// selectors, so follow those to their fully-qualified form.
+pub(crate) fn qualified_points(
+    ud: &[Details],
+    sequences: &Sequences,
+    points: Vec<usize>,
+) -> Vec<usize> {
+    let etest = if points.len() > 1 {
+        sequences.get(&points).and_then(|x| x.etest.as_ref())
+    } else {
+        ud[points[0]].etest.as_ref()
+    };
+
+    etest.and_then(|x| x.qualified.clone()).unwrap_or(points)
+}
+
+// CLDR data is XML, but we only need the five predefined entities.
+pub(crate) fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
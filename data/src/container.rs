@@ -0,0 +1,231 @@
+use std::convert::{TryFrom, TryInto};
+use std::io::{Read, Write};
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use color_eyre::eyre::{self, bail, ensure, OptionExt};
+
+use crate::schema::schema_enum;
+
+// Single-file alternative to the loose output files, so that data can
+// be deployed atomically and mismatched or corrupt data is detected.
+//
+// All integers are big endian, and strings are a length (u8) followed
+// by that many bytes of UTF-8.
+//
+// header: magic (8 bytes), format version (u16), Unicode version,
+// emoji version (strings), section count (u16)
+// directory, for each section: name (string), type (u8), offset,
+// length, checksum (u32)
+// header checksum (u32), covering the header and directory
+// section data, each starting at an offset aligned to 4 bytes
+//
+// Checksums are CRC-32 (ISO-HDLC, as used by zlib and PNG).
+pub const MAGIC: [u8; 8] = *b"charming";
+pub const FORMAT_VERSION: u16 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Container {
+    pub unicode_version: String,
+    pub emoji_version: String,
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub name: String,
+    pub r#type: SectionType,
+    pub data: Vec<u8>,
+}
+
+schema_enum! {
+    #[repr(u8)]
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum SectionType {
+        // Fixed-layout records, documented where the section is written.
+        Records = 0,
+        Json = 1,
+        Text = 2,
+        U8 = 3,
+        U16 = 4,
+        U32 = 5,
+        // A multi-stage trie of values for each point (see trie.rs).
+        SparseU8 = 6,
+        SparseU16 = 7,
+        U24 = 8,
+        SparseU24 = 9,
+        SparseU32 = 10,
+        // A binary string pool (see strings.rs).
+        Strings = 11,
+    }
+}
+
+impl TryFrom<u8> for SectionType {
+    type Error = eyre::Report;
+
+    fn try_from(value: u8) -> eyre::Result<Self> {
+        Ok(match value {
+            0 => Self::Records,
+            1 => Self::Json,
+            2 => Self::Text,
+            3 => Self::U8,
+            4 => Self::U16,
+            5 => Self::U32,
+            6 => Self::SparseU8,
+            7 => Self::SparseU16,
+            8 => Self::U24,
+            9 => Self::SparseU24,
+            10 => Self::SparseU32,
+            11 => Self::Strings,
+            _ => bail!("unknown section type {}", value),
+        })
+    }
+}
+
+impl Container {
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|x| x.name == name)
+    }
+
+    pub fn write(&self, mut sink: impl Write) -> eyre::Result<()> {
+        let mut header = Vec::default();
+        header.write_all(&MAGIC)?;
+        header.write_u16::<BigEndian>(FORMAT_VERSION)?;
+        write_string(&mut header, &self.unicode_version)?;
+        write_string(&mut header, &self.emoji_version)?;
+        let count = self
+            .sections
+            .len()
+            .try_into()
+            .expect("section count overflow");
+        header.write_u16::<BigEndian>(count)?;
+
+        let directory_len = self
+            .sections
+            .iter()
+            .map(|x| 1 + x.name.len() + 1 + 4 + 4 + 4)
+            .sum::<usize>();
+        let mut offset = align(header.len() + directory_len + 4);
+        for section in &self.sections {
+            write_string(&mut header, &section.name)?;
+            header.write_u8(section.r#type as u8)?;
+            header.write_u32::<BigEndian>(offset.try_into().expect("container overflow"))?;
+            let len = section.data.len();
+            header.write_u32::<BigEndian>(len.try_into().expect("section overflow"))?;
+            header.write_u32::<BigEndian>(crc32(&section.data))?;
+            offset = align(offset + len);
+        }
+        header.write_u32::<BigEndian>(crc32(&header))?;
+
+        sink.write_all(&header)?;
+        let mut offset = header.len();
+        for section in &self.sections {
+            sink.write_all(&[0; 3][..align(offset) - offset])?;
+            sink.write_all(&section.data)?;
+            offset = align(offset) + section.data.len();
+        }
+
+        Ok(())
+    }
+
+    // Reads a container, checking its format version and checksums.
+    pub fn read(bytes: &[u8]) -> eyre::Result<Self> {
+        let mut source = bytes;
+        let mut magic = [0; 8];
+        source.read_exact(&mut magic)?;
+        ensure!(magic == MAGIC, "not a container (bad magic)");
+        let version = source.read_u16::<BigEndian>()?;
+        ensure!(
+            version == FORMAT_VERSION,
+            "unsupported container format version {} (expected {})",
+            version,
+            FORMAT_VERSION
+        );
+        let unicode_version = read_string(&mut source)?;
+        let emoji_version = read_string(&mut source)?;
+
+        let mut directory = Vec::default();
+        for _ in 0..source.read_u16::<BigEndian>()? {
+            let name = read_string(&mut source)?;
+            let r#type = SectionType::try_from(source.read_u8()?)?;
+            let offset = source.read_u32::<BigEndian>()? as usize;
+            let len = source.read_u32::<BigEndian>()? as usize;
+            let checksum = source.read_u32::<BigEndian>()?;
+            directory.push((name, r#type, offset, len, checksum));
+        }
+        let header_len = bytes.len() - source.len();
+        let checksum = source.read_u32::<BigEndian>()?;
+        ensure!(
+            checksum == crc32(&bytes[..header_len]),
+            "container header checksum mismatch"
+        );
+
+        let mut sections = Vec::default();
+        for (name, r#type, offset, len, checksum) in directory {
+            let data = offset
+                .checked_add(len)
+                .and_then(|end| bytes.get(offset..end))
+                .ok_or_eyre(format!("section {} out of bounds", name))?;
+            ensure!(
+                checksum == crc32(data),
+                "section {} checksum mismatch",
+                name
+            );
+            sections.push(Section {
+                name,
+                r#type,
+                data: data.to_owned(),
+            });
+        }
+
+        Ok(Self {
+            unicode_version,
+            emoji_version,
+            sections,
+        })
+    }
+}
+
+fn align(offset: usize) -> usize {
+    (offset + 3) & !3
+}
+
+fn write_string(sink: &mut Vec<u8>, string: &str) -> eyre::Result<()> {
+    sink.write_u8(string.len().try_into().expect("string overflow"))?;
+    sink.write_all(string.as_bytes())?;
+
+    Ok(())
+}
+
+fn read_string(source: &mut &[u8]) -> eyre::Result<String> {
+    let mut result = vec![0; source.read_u8()?.into()];
+    source.read_exact(&mut result)?;
+
+    Ok(String::from_utf8(result)?)
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut result = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut x = i as u32;
+        let mut j = 0;
+        while j < 8 {
+            x = if x & 1 != 0 {
+                0xEDB88320 ^ (x >> 1)
+            } else {
+                x >> 1
+            };
+            j += 1;
+        }
+        result[i] = x;
+        i += 1;
+    }
+
+    result
+};
+
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |a, &x| {
+        CRC32_TABLE[((a ^ u32::from(x)) & 0xFF) as usize] ^ (a >> 8)
+    })
+}
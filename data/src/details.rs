@@ -1,12 +1,14 @@
 use enumflags2::{bitflags, BitFlags};
-use std::{rc::Rc, str::FromStr};
+use std::{collections::BTreeMap, rc::Rc, str::FromStr};
 
 use bon::Builder;
 use color_eyre::eyre::{self, bail};
 
+use crate::schema::schema_enum;
+
 #[derive(Debug, Default, Clone, PartialEq, Builder)]
 #[builder(on(Rc<str>, into))]
-pub(crate) struct Details {
+pub struct Details {
     #[builder(default)]
     pub bits: BitFlags<Bits>,
     #[builder(default)]
@@ -23,71 +25,146 @@ pub(crate) struct Details {
     pub hst: Option<HangulSyllableType>,
     pub hjsn: Option<Rc<str>>,
     pub hlvt: Option<(usize, usize, usize)>,
-    pub uhdef: Option<Rc<str>>,
-    pub uhman: Option<Rc<str>>,
+    #[builder(with = |unihan: &'static [(&'static str, &str)]| { unihan.iter().map(|&(k, v)| (k.into(), v.into())).collect() })]
+    #[builder(default)]
+    pub unihan: BTreeMap<Rc<str>, Rc<str>>,
+    pub uhrs: Option<(usize, usize, isize)>,
+    pub uhts: Option<usize>,
+    #[builder(default)]
+    pub variant: Vec<Variant>,
+    #[builder(default)]
+    pub nlnote: Vec<Note>,
+    #[builder(default)]
+    pub nlxref: Vec<usize>,
+    pub etest: Option<EmojiTest>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
-pub(crate) struct Alias {
+pub struct Alias {
     pub inner: Rc<str>,
     pub r#type: AliasType,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-#[repr(u8)]
-pub(crate) enum AliasType {
-    Correction = 0,
-    Control = 1,
-    Alternate = 2,
-    Figment = 3,
-    Abbreviation = 4,
-    Unicode1 = 5,
-    Cldr = 6,
+schema_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(u8)]
+    pub enum AliasType {
+        Correction = 0,
+        Control = 1,
+        Alternate = 2,
+        Figment = 3,
+        Abbreviation = 4,
+        Unicode1 = 5,
+        Cldr = 6,
+        CldrKeyword = 7,
+        Informal = 8,
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-#[repr(u8)]
-pub(crate) enum GraphemeBreak {
-    Cr = 1,
-    Lf = 2,
-    Control = 3,
-    Extend = 4,
-    Zwj = 5,
-    RegionalIndicator = 6,
-    Prepend = 7,
-    SpacingMark = 8,
-    HangulL = 9,
-    HangulV = 10,
-    HangulT = 11,
-    HangulLV = 12,
-    HangulLVT = 13,
-}
-
-#[repr(u8)]
-#[derive(Debug, Clone, Copy)]
-#[bitflags]
-pub(crate) enum Bits {
-    KdefinitionExists = 1 << 0,
-    IsSpaceSeparator = 1 << 2,
-    IsAnyMark = 1 << 3,
-    DerivedNameNr1 = 1 << 4,
-    DerivedNameNr2 = 1 << 5,
-}
-
-#[repr(u8)]
-#[derive(Debug, Clone, Copy)]
-#[bitflags]
-pub(crate) enum EmojiBits {
-    Emoji = 1 << 0,
-    ExtendedPictographic = 1 << 1,
-    EmojiComponent = 1 << 2,
-    EmojiPresentation = 1 << 3,
-    EmojiModifier = 1 << 4,
-    EmojiModifierBase = 1 << 5,
+#[derive(Debug, Clone, PartialEq)]
+pub struct Note {
+    pub inner: Rc<str>,
+    pub r#type: NoteType,
+}
+
+schema_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(u8)]
+    pub enum NoteType {
+        Informative = 0,
+        Variation = 1,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub point: usize,
+    pub r#type: VariantType,
+}
+
+schema_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(u8)]
+    pub enum VariantType {
+        Traditional = 0,
+        Simplified = 1,
+        Semantic = 2,
+        SpecializedSemantic = 3,
+        Z = 4,
+        Spoofing = 5,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmojiTest {
+    pub order: usize,
+    pub group: Rc<str>,
+    pub subgroup: Rc<str>,
+    pub version: Rc<str>,
+    pub status: EmojiStatus,
+    pub qualified: Option<Vec<usize>>,
+}
+
+schema_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(u8)]
+    pub enum EmojiStatus {
+        Component = 1,
+        FullyQualified = 2,
+        MinimallyQualified = 3,
+        Unqualified = 4,
+    }
+}
+
+schema_enum! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(u8)]
+    pub enum GraphemeBreak {
+        Cr = 1,
+        Lf = 2,
+        Control = 3,
+        Extend = 4,
+        Zwj = 5,
+        RegionalIndicator = 6,
+        Prepend = 7,
+        SpacingMark = 8,
+        HangulL = 9,
+        HangulV = 10,
+        HangulT = 11,
+        HangulLV = 12,
+        HangulLVT = 13,
+    }
+}
+
+schema_enum! {
+    #[repr(u8)]
+    #[derive(Debug, Clone, Copy)]
+    #[bitflags]
+    pub enum Bits {
+        KdefinitionExists = 1 << 0,
+        IsSpaceSeparator = 1 << 2,
+        IsAnyMark = 1 << 3,
+        DerivedNameNr1 = 1 << 4,
+        DerivedNameNr2 = 1 << 5,
+    }
+}
+
+schema_enum! {
+    #[repr(u8)]
+    #[derive(Debug, Clone, Copy)]
+    #[bitflags]
+    pub enum EmojiBits {
+        Emoji = 1 << 0,
+        ExtendedPictographic = 1 << 1,
+        EmojiComponent = 1 << 2,
+        EmojiPresentation = 1 << 3,
+        EmojiModifier = 1 << 4,
+        EmojiModifierBase = 1 << 5,
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-pub(crate) enum HangulSyllableType {
+pub enum HangulSyllableType {
     Lv,
     Lvt,
 }
@@ -116,6 +193,36 @@ impl FromStr for AliasType {
     }
 }
 
+impl FromStr for VariantType {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "kTraditionalVariant" => Self::Traditional,
+            "kSimplifiedVariant" => Self::Simplified,
+            "kSemanticVariant" => Self::Semantic,
+            "kSpecializedSemanticVariant" => Self::SpecializedSemantic,
+            "kZVariant" => Self::Z,
+            "kSpoofingVariant" => Self::Spoofing,
+            _ => bail!("unknown Unihan variant field: {s}"),
+        })
+    }
+}
+
+impl FromStr for EmojiStatus {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "component" => Self::Component,
+            "fully-qualified" => Self::FullyQualified,
+            "minimally-qualified" => Self::MinimallyQualified,
+            "unqualified" => Self::Unqualified,
+            _ => bail!("unknown emoji-test.txt status: {s}"),
+        })
+    }
+}
+
 impl FromStr for GraphemeBreak {
     type Err = eyre::Report;
 
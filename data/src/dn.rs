@@ -0,0 +1,70 @@
+use std::collections::BTreeMap;
+
+use color_eyre::eyre::{self, bail};
+use regex::Captures;
+
+use crate::captures::CapturesExt;
+use crate::details::Details;
+use crate::dynamic::name_property;
+
+// Names from Extracted/DerivedName.txt, where ranges of names derived by
+// rule NR2 are written as a prefix with a * wildcard.
+#[derive(Debug, Default)]
+pub(crate) struct DerivedNames {
+    pub explicit: BTreeMap<usize, String>,
+    pub wildcards: Vec<(usize, usize, String)>,
+}
+
+pub(crate) fn dn_handler(sink: &mut DerivedNames, captures: Captures) -> eyre::Result<()> {
+    let first = usize::from_str_radix(captures.try_name("first")?, 16)?;
+    let last = captures
+        .name("last")
+        .map_or(Ok(first), |x| usize::from_str_radix(x.as_str(), 16))?;
+    let name = captures.try_name("name")?;
+
+    if let Some(prefix) = name.strip_suffix('*') {
+        sink.wildcards.push((first, last, prefix.to_owned()));
+    } else {
+        assert_eq!(first, last);
+        sink.explicit.insert(first, name.to_owned());
+    }
+
+    Ok(())
+}
+
+impl DerivedNames {
+    pub fn get(&self, point: usize) -> Option<String> {
+        if let Some(name) = self.explicit.get(&point) {
+            return Some(name.clone());
+        }
+
+        self.wildcards
+            .iter()
+            .find(|&&(first, last, _)| first <= point && point <= last)
+            .map(|(_, _, prefix)| format!("{}{:04X}", prefix, point))
+    }
+}
+
+// Checks the Name property of every point against DerivedName.txt,
+// reporting all of the mismatches before failing.
+pub(crate) fn verify_derived_names(data: &[Details], derived: &DerivedNames) -> eyre::Result<()> {
+    let mut mismatches = 0;
+
+    for point in 0..data.len() {
+        let actual = name_property(data, point);
+        let expected = derived.get(point);
+        if actual != expected {
+            eprintln!(
+                "Name mismatch at U+{:04X}: ours {:?}, DerivedName.txt {:?}",
+                point, actual, expected
+            );
+            mismatches += 1;
+        }
+    }
+
+    if mismatches > 0 {
+        bail!("{} names disagree with DerivedName.txt", mismatches);
+    }
+
+    Ok(())
+}
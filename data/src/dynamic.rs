@@ -1,66 +1,113 @@
-use crate::details::{Details, HangulSyllableType};
+use std::collections::HashMap;
+
+use color_eyre::eyre::{self, bail};
+
+use crate::details::{Bits, Details, HangulSyllableType};
+use crate::dn::DerivedNames;
 
 // Table 4-8.  Name Derivation Rule Prefix Strings
 // https://www.unicode.org/versions/Unicode16.0.0/core-spec/chapter-4/#G2082
-pub(crate) const NAME_RULES: [(usize, usize, NameRule, &str); 19] = [
-    (0xAC00, 0xD7A3, NameRule::NR1, "HANGUL SYLLABLE "),
-    (0x3400, 0x4DBF, NameRule::NR2, "CJK UNIFIED IDEOGRAPH-"),
-    (0x4E00, 0x9FFF, NameRule::NR2, "CJK UNIFIED IDEOGRAPH-"),
-    (0x20000, 0x2A6DF, NameRule::NR2, "CJK UNIFIED IDEOGRAPH-"),
-    (0x2A700, 0x2B739, NameRule::NR2, "CJK UNIFIED IDEOGRAPH-"),
-    (0x2B740, 0x2B81D, NameRule::NR2, "CJK UNIFIED IDEOGRAPH-"),
-    (0x2B820, 0x2CEA1, NameRule::NR2, "CJK UNIFIED IDEOGRAPH-"),
-    (0x2CEB0, 0x2EBE0, NameRule::NR2, "CJK UNIFIED IDEOGRAPH-"),
-    (0x2EBF0, 0x2EE5D, NameRule::NR2, "CJK UNIFIED IDEOGRAPH-"),
-    (0x30000, 0x3134A, NameRule::NR2, "CJK UNIFIED IDEOGRAPH-"),
-    (0x31350, 0x323AF, NameRule::NR2, "CJK UNIFIED IDEOGRAPH-"),
-    (0x13460, 0x143FA, NameRule::NR2, "EGYPTIAN HIEROGLYPH-"),
-    (0x17000, 0x187F7, NameRule::NR2, "TANGUT IDEOGRAPH-"),
-    (0x18D00, 0x18D08, NameRule::NR2, "TANGUT IDEOGRAPH-"),
-    (
-        0x18B00,
-        0x18CD5,
-        NameRule::NR2,
-        "KHITAN SMALL SCRIPT CHARACTER-",
-    ),
-    (0x1B170, 0x1B2FB, NameRule::NR2, "NUSHU CHARACTER-"),
-    (
-        0xF900,
-        0xFA6D,
-        NameRule::NR2,
-        "CJK COMPATIBILITY IDEOGRAPH-",
-    ),
-    (
-        0xFA70,
-        0xFAD9,
-        NameRule::NR2,
-        "CJK COMPATIBILITY IDEOGRAPH-",
-    ),
-    (
-        0x2F800,
-        0x2FA1D,
-        NameRule::NR2,
-        "CJK COMPATIBILITY IDEOGRAPH-",
-    ),
-];
-
-pub(crate) enum NameRule {
+const HANGUL_SYLLABLE_PREFIX: &str = "HANGUL SYLLABLE ";
+
+pub type NameRules = Vec<(usize, usize, NameRule, String)>;
+
+// Derives the ranges of names derived by rule, from the wildcards in
+// DerivedName.txt (and its Hangul syllable names), checking them against
+// the First/Last ranges in UnicodeData.txt.
+pub(crate) fn derive_name_rules(
+    data: &[Details],
+    ud_ranges: &HashMap<String, (usize, Option<usize>)>,
+    derived: &DerivedNames,
+) -> eyre::Result<NameRules> {
+    let mut result = NameRules::default();
+
+    for (first, last, prefix) in &derived.wildcards {
+        let rule = if prefix == HANGUL_SYLLABLE_PREFIX {
+            NameRule::NR1
+        } else {
+            NameRule::NR2
+        };
+        result.push((*first, *last, rule, prefix.clone()));
+    }
+
+    // DerivedName.txt lists each Hangul syllable name in full
+    let hangul = derived
+        .explicit
+        .iter()
+        .filter(|(_, name)| name.starts_with(HANGUL_SYLLABLE_PREFIX))
+        .map(|(&point, _)| point)
+        .collect::<Vec<_>>();
+    if let (Some(&first), Some(&last)) = (hangul.first(), hangul.last()) {
+        if hangul.len() != last - first + 1 {
+            bail!("Hangul syllable names in DerivedName.txt are not contiguous");
+        }
+        result.push((
+            first,
+            last,
+            NameRule::NR1,
+            HANGUL_SYLLABLE_PREFIX.to_owned(),
+        ));
+    }
+
+    result.sort_by_key(|&(first, ..)| first);
+
+    // Each range in UnicodeData.txt should either be exactly one rule, or
+    // have no names at all (like surrogates and private use).
+    for (name, &(first, last)) in ud_ranges {
+        let last = last.expect("missing Last in UnicodeData");
+        let overlapping = result
+            .iter()
+            .filter(|&&(p, q, ..)| p <= last && first <= q)
+            .collect::<Vec<_>>();
+        let named = derived.explicit.range(first..=last).next().is_some();
+        match &*overlapping {
+            [] if !named => {}
+            [(p, q, ..)] if (*p, *q) == (first, last) => {}
+            _ => bail!(
+                "UnicodeData.txt range {} (U+{:04X}..U+{:04X}) disagrees with DerivedName.txt",
+                name,
+                first,
+                last
+            ),
+        }
+    }
+
+    // Rules for characters listed individually in UnicodeData.txt should
+    // only cover points with explicit names there.
+    for (first, last, _, prefix) in &result {
+        let in_ud_range = ud_ranges
+            .values()
+            .any(|&(p, q)| (p, q) == (*first, Some(*last)));
+        if !in_ud_range && (*first..=*last).any(|i| data[i].name.is_none()) {
+            bail!(
+                "DerivedName.txt rule {} (U+{:04X}..U+{:04X}) covers unnamed points in UnicodeData.txt",
+                prefix,
+                first,
+                last
+            );
+        }
+    }
+
+    Ok(result)
+}
+
+pub enum NameRule {
     NR1,
     NR2,
 }
 
 // 3.12  Conjoining Jamo Behavior
-pub(crate) fn hangul_lvt_indices(data: &[Details], point: usize) -> Option<(usize, usize, usize)> {
-    const S_BASE: usize = 0xAC00;
-    // const L_BASE: usize = 0x1100;
-    // const V_BASE: usize = 0x1161;
-    // const T_BASE: usize = 0x11A7;
-    // const L_COUNT: usize = 19;
-    const V_COUNT: usize = 21;
-    const T_COUNT: usize = 28;
-    const N_COUNT: usize = V_COUNT * T_COUNT;
-    // const S_COUNT: usize = L_COUNT * N_COUNT;
+const S_BASE: usize = 0xAC00;
+const L_BASE: usize = 0x1100;
+const V_BASE: usize = 0x1161;
+const T_BASE: usize = 0x11A7;
+// const L_COUNT: usize = 19;
+const V_COUNT: usize = 21;
+const T_COUNT: usize = 28;
+const N_COUNT: usize = V_COUNT * T_COUNT;
+// const S_COUNT: usize = L_COUNT * N_COUNT;
 
+pub(crate) fn hangul_lvt_indices(data: &[Details], point: usize) -> Option<(usize, usize, usize)> {
     let s = point;
     let hst = data[s].hst;
 
@@ -77,3 +124,35 @@ pub(crate) fn hangul_lvt_indices(data: &[Details], point: usize) -> Option<(usiz
         None => None,
     }
 }
+
+// Returns the Jamo_Short_Name part of a Hangul syllable name (rule NR1),
+// or None if the point is not a syllable or a short name is missing.
+pub(crate) fn hangul_syllable_short_name(data: &[Details], point: usize) -> Option<String> {
+    let (l, v, t) = data[point].hlvt?;
+    let ln = data[L_BASE + l].hjsn.as_deref()?;
+    let vn = data[V_BASE + v].hjsn.as_deref()?;
+    let tn = match t {
+        0 => "",
+        t => data[T_BASE + t].hjsn.as_deref()?,
+    };
+
+    Some(format!("{}{}{}", ln, vn, tn))
+}
+
+// Returns the Name property of a point, after derived names have been
+// replaced by a prefix (dnrp) and a rule in the name rules loop.
+pub fn name_property(data: &[Details], point: usize) -> Option<String> {
+    let details = &data[point];
+
+    if let Some(name) = &details.name {
+        Some(name.to_string())
+    } else if details.bits.contains(Bits::DerivedNameNr1) {
+        let prefix = details.dnrp.as_deref()?;
+        hangul_syllable_short_name(data, point).map(|x| format!("{}{}", prefix, x))
+    } else if details.bits.contains(Bits::DerivedNameNr2) {
+        let prefix = details.dnrp.as_deref()?;
+        Some(format!("{}{:04X}", prefix, point))
+    } else {
+        None
+    }
+}
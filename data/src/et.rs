@@ -1,34 +1,100 @@
-use color_eyre::eyre;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use color_eyre::eyre::{self, OptionExt};
 use regex::Captures;
 
 use crate::captures::CapturesExt;
-use crate::details::{Alias, AliasType, Details};
+use crate::details::{Alias, AliasType, Details, EmojiStatus, EmojiTest};
 use crate::pool::Popularity;
-use crate::sequence::Sequences;
+use crate::sequence::{SequenceBits, Sequences};
+
+#[derive(Debug, Default)]
+pub(crate) struct EmojiTestState {
+    order: usize,
+    group: Option<Rc<str>>,
+    subgroup: Option<Rc<str>>,
+    fully_qualified: HashMap<String, Vec<usize>>,
+}
 
 pub(crate) fn et_handler(
     popularity: &mut Popularity,
+    state: &mut EmojiTestState,
     sink: &mut [Details],
     sequences: &mut Sequences,
     captures: Captures,
 ) -> eyre::Result<()> {
+    if let Some(group) = captures.name("group") {
+        state.group = Some(popularity.vote(group.as_str()));
+        state.subgroup = None;
+        return Ok(());
+    }
+    if let Some(subgroup) = captures.name("subgroup") {
+        state.subgroup = Some(popularity.vote(subgroup.as_str()));
+        return Ok(());
+    }
+
     let points = captures
         .try_name("points")?
         .split(" ")
         .map(|x| usize::from_str_radix(x, 16))
         .collect::<Result<Vec<_>, _>>()?;
+    let status: EmojiStatus = captures.try_name("status")?.parse()?;
+    let version = captures.try_name("version")?;
     let name = captures.try_name("name")?;
 
+    // Minimally-qualified and unqualified emoji are listed after their
+    // fully-qualified form, with the same name.
+    let qualified = match status {
+        EmojiStatus::Component | EmojiStatus::FullyQualified => {
+            state
+                .fully_qualified
+                .insert(name.to_owned(), points.clone());
+            None
+        }
+        EmojiStatus::MinimallyQualified | EmojiStatus::Unqualified => Some(
+            state
+                .fully_qualified
+                .get(name)
+                .ok_or_eyre("missing fully-qualified form in emoji-test.txt")?
+                .clone(),
+        ),
+    };
+    let named = qualified.is_none();
+    let etest = EmojiTest {
+        order: state.order,
+        group: state
+            .group
+            .clone()
+            .ok_or_eyre("missing group in emoji-test.txt")?,
+        subgroup: state
+            .subgroup
+            .clone()
+            .ok_or_eyre("missing subgroup in emoji-test.txt")?,
+        version: popularity.vote(&format!("Emoji {}", version)),
+        status,
+        qualified,
+    };
+    state.order += 1;
+
     if points.len() > 1 {
         // eprintln!("{} {}", captures.name_ok("points")?, captures.name_ok("name")?);
-        sequences.insert(&points, popularity.vote(name));
+        let sequence = sequences.entry(&points);
+        sequence.bits |= SequenceBits::EmojiTest;
+        if named {
+            sequence.push_name(popularity.vote(name));
+        }
+        sequence.etest = Some(etest);
         return Ok(());
     }
 
-    sink[points[0]].alias.push(Alias {
-        inner: popularity.vote(name),
-        r#type: AliasType::Cldr,
-    });
+    if named {
+        sink[points[0]].alias.push(Alias {
+            inner: popularity.vote(name),
+            r#type: AliasType::Cldr,
+        });
+    }
+    sink[points[0]].etest = Some(etest);
 
     Ok(())
 }
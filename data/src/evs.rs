@@ -0,0 +1,36 @@
+use color_eyre::eyre;
+use regex::Captures;
+
+use crate::captures::CapturesExt;
+use crate::details::Details;
+use crate::pool::Popularity;
+use crate::sequence::{SequenceBits, Sequences};
+
+pub(crate) fn evs_handler(
+    popularity: &mut Popularity,
+    ud: &[Details],
+    sequences: &mut Sequences,
+    captures: Captures,
+) -> eyre::Result<()> {
+    let points = captures
+        .try_name("points")?
+        .split(" ")
+        .map(|x| usize::from_str_radix(x, 16))
+        .collect::<Result<Vec<_>, _>>()?;
+    let style = captures.try_name("style")?;
+
+    // Name each sequence after its base character, so that the text and
+    // emoji style of each character can be told apart.
+    let name = match &ud[points[0]].name {
+        Some(name) => format!("{} ({})", name, style),
+        None => format!("U+{:04X} ({})", points[0], style),
+    };
+
+    sequences.insert(
+        &points,
+        popularity.vote(&name),
+        SequenceBits::EmojiVariation,
+    );
+
+    Ok(())
+}
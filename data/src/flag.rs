@@ -0,0 +1,127 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryInto;
+use std::iter::once;
+
+use crate::pool::Popularity;
+use crate::sequence::{SequenceBits, Sequences};
+
+const REGIONAL_INDICATOR_A: usize = 0x1F1E6;
+const WAVING_BLACK_FLAG: usize = 0x1F3F4;
+const TAG_BASE: usize = 0xE0000;
+const CANCEL_TAG: usize = 0xE007F;
+
+// UTS #51 § 1.4.5 and Annex B: emoji flag sequences are pairs of
+// regional indicators for a CLDR region code, and emoji tag sequences
+// for flags are a black flag followed by a CLDR subdivision code in
+// tag characters, terminated by a cancel tag.
+//
+// Flags for the regions and subdivisions in CLDR, and flags from the
+// other inputs that have no name, are named with the name from CLDR if
+// it has one, or just the code if not. Flags that already have a name,
+// like those in emoji-test.txt, are left alone. Nothing is derived for
+// the regions or subdivisions if their CLDR file is missing.
+pub(crate) fn derive_flag_names(
+    popularity: &mut Popularity,
+    territories: &BTreeMap<String, String>,
+    subdivisions: &BTreeMap<String, String>,
+    sequences: &mut Sequences,
+) {
+    if !territories.is_empty() {
+        let mut codes = territories.keys().cloned().collect::<BTreeSet<_>>();
+        codes.extend(
+            sequences
+                .buckets
+                .values()
+                .flatten()
+                .filter_map(|x| region_code(&x.points)),
+        );
+        for code in codes {
+            let points = code
+                .chars()
+                .map(|x| REGIONAL_INDICATOR_A + (x as usize - 'A' as usize))
+                .collect::<Vec<_>>();
+            let name = territories.get(&code);
+            insert(popularity, sequences, &points, &code, name);
+        }
+    }
+
+    if !subdivisions.is_empty() {
+        let mut codes = subdivisions.keys().cloned().collect::<BTreeSet<_>>();
+        codes.extend(
+            sequences
+                .buckets
+                .values()
+                .flatten()
+                .filter_map(|x| subdivision_code(&x.points)),
+        );
+        for code in codes {
+            let points = once(WAVING_BLACK_FLAG)
+                .chain(code.chars().map(|x| TAG_BASE + x as usize))
+                .chain(once(CANCEL_TAG))
+                .collect::<Vec<_>>();
+            let name = subdivisions.get(&code);
+            let (region, subdivision) = code.split_at(2);
+            let code = format!("{}-{}", region, subdivision).to_uppercase();
+            insert(popularity, sequences, &points, &code, name);
+        }
+    }
+}
+
+// Returns the region code (like GB) in a pair of regional indicators,
+// if the points are one.
+fn region_code(points: &[usize]) -> Option<String> {
+    if points.len() != 2 {
+        return None;
+    }
+
+    points
+        .iter()
+        .map(|&x| {
+            let offset = x.checked_sub(REGIONAL_INDICATOR_A)?;
+            (offset < 26).then(|| (b'A' + offset as u8) as char)
+        })
+        .collect()
+}
+
+// Returns the subdivision code (like gbsct) in a tag sequence for a
+// flag, if the points are one.
+fn subdivision_code(points: &[usize]) -> Option<String> {
+    let (&first, rest) = points.split_first()?;
+    let (&last, tags) = rest.split_last()?;
+    if first != WAVING_BLACK_FLAG || last != CANCEL_TAG || !(3..=6).contains(&tags.len()) {
+        return None;
+    }
+    let code = tags
+        .iter()
+        .map(|&x| char::from_u32(x.checked_sub(TAG_BASE)?.try_into().ok()?))
+        .collect::<Option<String>>()?;
+    let (region, subdivision) = code.split_at_checked(2)?;
+
+    (region.chars().all(|x| x.is_ascii_lowercase())
+        && subdivision
+            .chars()
+            .all(|x| x.is_ascii_lowercase() || x.is_ascii_digit()))
+    .then_some(code)
+}
+
+fn insert(
+    popularity: &mut Popularity,
+    sequences: &mut Sequences,
+    points: &[usize],
+    code: &str,
+    name: Option<&String>,
+) {
+    if sequences.get(points).is_some_and(|x| !x.names.is_empty()) {
+        return;
+    }
+
+    // flags in emoji-test.txt are named there, so these are not RGI
+    let name = match name {
+        Some(name) => format!("flag: {} ({}, non-RGI)", code, name),
+        None => format!("flag: {} (non-RGI)", code),
+    };
+
+    let sequence = sequences.entry(points);
+    sequence.push_name(popularity.vote(&name));
+    sequence.bits |= SequenceBits::DerivedFlag;
+}
@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use color_eyre::eyre::{self, bail, ensure, OptionExt};
+use regex::{Captures, Regex};
+
+use crate::captures::CapturesExt;
+use crate::pool::Popularity;
+use crate::sequence::{SequenceBits, Sequences};
+
+pub(crate) fn ivdc_handler(
+    sink: &mut HashMap<String, Regex>,
+    captures: Captures,
+) -> eyre::Result<()> {
+    let collection = captures.try_name("collection")?;
+    let pattern = captures.try_name("pattern")?;
+
+    sink.insert(
+        collection.to_owned(),
+        Regex::new(&format!("^(?:{})$", pattern))?,
+    );
+
+    Ok(())
+}
+
+pub(crate) fn ivd_handler(
+    popularity: &mut Popularity,
+    collections: &HashMap<String, Regex>,
+    sequences: &mut Sequences,
+    captures: Captures,
+) -> eyre::Result<()> {
+    let points = captures
+        .try_name("points")?
+        .split(" ")
+        .map(|x| usize::from_str_radix(x, 16))
+        .collect::<Result<Vec<_>, _>>()?;
+    let collection = captures.try_name("collection")?;
+    let identifier = captures.try_name("identifier")?;
+
+    // Ideographic variation sequences are always a base character
+    // followed by one of VS17 through VS256.
+    ensure!(
+        points.len() == 2 && (0xE0100..=0xE01EF).contains(&points[1]),
+        "bad ideographic variation sequence in IVD_Sequences.txt: {}",
+        captures.try_name("points")?
+    );
+
+    let pattern = collections
+        .get(collection)
+        .ok_or_eyre("unknown collection in IVD_Sequences.txt")?;
+    if !pattern.is_match(identifier) {
+        bail!("bad sequence identifier for {collection}: {identifier}");
+    }
+
+    sequences.insert(
+        &points,
+        popularity.vote(&format!("{} {}", collection, identifier)),
+        SequenceBits::Ivd,
+    );
+
+    Ok(())
+}
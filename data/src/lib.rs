@@ -0,0 +1,502 @@
+mod age;
+mod block;
+mod captures;
+mod cjkr;
+mod cldr;
+pub mod container;
+pub mod details;
+mod dn;
+pub mod dynamic;
+mod ed;
+mod et;
+mod evs;
+mod flag;
+mod gbp;
+mod gc;
+mod hst;
+mod ivd;
+mod jamo;
+mod locale;
+mod na;
+pub mod names;
+mod nl;
+mod ns;
+mod page;
+mod parse;
+pub mod pinyin;
+mod pool;
+mod range;
+mod schema;
+pub mod sequence;
+pub mod strings;
+mod sv;
+mod trie;
+mod uax29;
+mod ud;
+mod uirg;
+mod ur;
+mod uv;
+mod version;
+mod write;
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::rc::Rc;
+
+use color_eyre::eyre::{self, bail, OptionExt};
+use regex::Regex;
+
+use crate::age::age_handler;
+use crate::block::block_handler;
+use crate::cjkr::{cjkr_handler, Radical};
+use crate::cldr::{annotation_handler, cldr_name_handler};
+use crate::details::{Bits, Details};
+use crate::dn::{dn_handler, verify_derived_names, DerivedNames};
+use crate::dynamic::{
+    derive_name_rules, hangul_lvt_indices, hangul_syllable_short_name, NameRule, NameRules,
+};
+use crate::ed::ed_handler;
+use crate::et::{et_handler, EmojiTestState};
+use crate::evs::evs_handler;
+use crate::flag::derive_flag_names;
+use crate::gbp::gbp_handler;
+use crate::gc::gc_handler;
+use crate::hst::hst_handler;
+use crate::ivd::{ivd_handler, ivdc_handler};
+use crate::jamo::jamo_handler;
+use crate::locale::{locale_annotation_handler, Locale};
+use crate::na::na_handler;
+use crate::nl::{nl_handler, NamesListState};
+use crate::ns::ns_handler;
+use crate::parse::{parse, parse_if_exists};
+use crate::pool::Popularity;
+use crate::sequence::{SequenceBits, Sequences};
+use crate::sv::sv_handler;
+use crate::ud::{process_ud_ranges, ud_handler, ud_range_handler};
+use crate::uirg::uirg_handler;
+use crate::ur::ur_handler;
+use crate::uv::uv_handler;
+use crate::version::version_handler;
+
+pub use crate::uirg::RESIDUAL_OFFSET;
+pub use crate::ur::DEFAULT_UNIHAN_FIELDS;
+pub use crate::write::{write_all, write_chunked, write_container};
+
+trait OptionRcExt {
+    fn map_clone(&self) -> Self;
+}
+
+impl OptionRcExt for Option<Rc<str>> {
+    fn map_clone(&self) -> Self {
+        self.as_ref().map(|x| x.clone())
+    }
+}
+
+// Everything parsed from the input files, ready to be written.
+pub struct Ucd {
+    pub unicode_version: String,
+    pub emoji_version: String,
+    pub ud: Vec<Details>,
+    pub sequences: Sequences,
+    pub name_rules: NameRules,
+    popularity: Popularity,
+    radicals: Vec<Radical>,
+    names_list: NamesListState,
+    locales: Vec<(String, Locale)>,
+    unihan_fields: Vec<String>,
+}
+
+// Parses the input files in dir, plus names and keywords for each of
+// the given CLDR locales, keeping the given Unihan_Readings.txt fields.
+pub fn load(dir: &Path, locale_codes: &[&str], unihan_fields: &[&str]) -> eyre::Result<Ucd> {
+    // locale codes become part of output paths, so allow only things that
+    // look like CLDR locale ids (like ja, pt_BR, zh_Hant_HK)
+    let locale_code = Regex::new(r"^[A-Za-z]{2,3}([_-][A-Za-z0-9]{2,8})*$")?;
+    for &code in locale_codes {
+        if !locale_code.is_match(code) {
+            bail!("bad locale code: {:?}", code);
+        }
+    }
+
+    let mut gc_labels = HashMap::default();
+
+    parse(
+        &mut gc_labels,
+        gc_handler,
+        dir.join("PropertyValueAliases.txt"),
+        None,
+        r"^gc *; *(?P<key>[^ ]+) *; *(?P<value>([^ ]+))",
+    )?;
+
+    let mut popularity = Popularity::default();
+    let mut ud = points();
+    let mut ud_ranges = HashMap::default();
+
+    parse(
+        &mut ud,
+        |_, captures| ud_range_handler(&mut ud_ranges, captures),
+        dir.join("UnicodeData.txt"),
+        "ranges",
+        r"^(?P<point>[0-9A-F]+);<(?P<name>[^;]+), (?P<kind>First|Last)>",
+    )?;
+
+    parse(
+        &mut ud,
+        |sink, captures| ud_handler(&gc_labels, &mut popularity, sink, captures),
+        dir.join("UnicodeData.txt"),
+        "all",
+        r"^(?P<point>[0-9A-F]+);(?P<name>[^;]+);(?P<gc>[^;]+);(?:[^;]*;){7}(?P<nau1>[^;]+)?",
+    )?;
+
+    let ud_range_firsts = process_ud_ranges(&ud_ranges);
+
+    for i in 0..ud.len() {
+        if let Some(&first) = ud_range_firsts.get(&i) {
+            ud[i] = ud[first].clone();
+            ud[i].name = None;
+        }
+    }
+
+    parse(
+        &mut ud,
+        |sink, captures| block_handler(&mut popularity, sink, captures),
+        dir.join("Blocks.txt"),
+        None,
+        r"^(?P<first>[0-9A-F]+)[.][.](?P<last>[0-9A-F]+); (?P<value>.+)",
+    )?;
+
+    parse(
+        &mut ud,
+        |sink, captures| age_handler(&mut popularity, sink, captures),
+        dir.join("DerivedAge.txt"),
+        None,
+        r"^(?P<first>[0-9A-F]+)(?:[.][.](?P<last>[0-9A-F]+))?\s*;\s*(?P<value>[^ ]+)",
+    )?;
+
+    let mut unicode_version = None;
+    parse(
+        &mut unicode_version,
+        version_handler,
+        dir.join("DerivedAge.txt"),
+        "version",
+        r"^# DerivedAge-(?P<version>[0-9.]+)[.]txt$",
+    )?;
+    let unicode_version = unicode_version.ok_or_eyre("missing version in DerivedAge.txt")?;
+
+    parse(
+        &mut ud,
+        |sink, captures| hst_handler(sink, captures),
+        dir.join("HangulSyllableType.txt"),
+        None,
+        r"^(?P<first>[0-9A-F]+)(?:[.][.](?P<last>[0-9A-F]+))?\s*;\s*(?P<value>[^ ]+)",
+    )?;
+
+    parse(
+        &mut ud,
+        |sink, captures| jamo_handler(&mut popularity, sink, captures),
+        dir.join("Jamo.txt"),
+        None,
+        r"^(?P<point>[0-9A-F]+)\s*;\s*(?P<value>[^ #]*)",
+    )?;
+
+    for i in 0..ud.len() {
+        ud[i].hlvt = hangul_lvt_indices(&ud, i);
+    }
+
+    parse(
+        &mut ud,
+        |sink, captures| na_handler(&mut popularity, sink, captures),
+        dir.join("NameAliases.txt"),
+        None,
+        r"^(?P<point>[0-9A-F]+);(?P<alias>[^;]+);(?P<type>[^;]+)",
+    )?;
+
+    let mut names_list = NamesListState::default();
+    parse(
+        &mut ud,
+        |sink, captures| nl_handler(&mut popularity, &mut names_list, sink, captures),
+        dir.join("NamesList.txt"),
+        None,
+        r"^(?:(?P<point>[0-9A-F]{4,6})\t.*|(?P<block>@@)\t.*|@\t\t(?P<subheader>.+)|\t(?P<kind>[=*~x]) (?P<value>.+))$",
+    )?;
+    names_list.finish_subheader();
+
+    let mut derived_names = DerivedNames::default();
+    parse(
+        &mut derived_names,
+        dn_handler,
+        dir.join("DerivedName.txt"),
+        None,
+        r"^(?P<first>[0-9A-F]+)(?:[.][.](?P<last>[0-9A-F]+))? *; (?P<name>.+)$",
+    )?;
+    let name_rules = derive_name_rules(&ud, &ud_ranges, &derived_names)?;
+
+    // Some characters have names that can be derived by algorithmic
+    // rules. UnicodeData.txt often uses ranges (see above) to define
+    // properties for these characters in bulk, but others are listed
+    // individually due to their unique properties.
+    for (first, last, rule, prefix) in &name_rules {
+        for i in *first..=*last {
+            // Each character with a derived name should either have
+            // no explicit name (iff defined in bulk) or an explicit
+            // name that matches its derived name.
+            let derived = derived_name(&ud, &name_rules, i).expect("failed to derive name");
+            let item = &mut ud[i];
+            assert!(item.name.as_deref().is_none_or(|x| x == derived));
+
+            // Strip out all derived names from output data, to avoid
+            // polluting string pool and client heap.
+            item.name = None;
+
+            item.dnrp = Some(popularity.vote(prefix));
+            item.bits |= match rule {
+                NameRule::NR1 => Bits::DerivedNameNr1,
+                NameRule::NR2 => Bits::DerivedNameNr2,
+            };
+        }
+    }
+
+    println!("Verifying names against DerivedName.txt ...");
+    verify_derived_names(&ud, &derived_names)?;
+
+    parse(
+        &mut ud,
+        |sink, captures| ur_handler(&mut popularity, sink, captures),
+        dir.join("Unihan_Readings.txt"),
+        None,
+        &format!(
+            r"^U[+](?P<point>[0-9A-F]+)\t(?P<key>{})\t(?P<value>.+)",
+            unihan_fields
+                .iter()
+                .map(|x| regex::escape(x))
+                .collect::<Vec<_>>()
+                .join("|")
+        ),
+    )?;
+
+    parse(
+        &mut ud,
+        |sink, captures| uirg_handler(sink, captures),
+        dir.join("Unihan_IRGSources.txt"),
+        None,
+        r"^U[+](?P<point>[0-9A-F]+)\t(?P<key>kRSUnicode|kTotalStrokes)\t(?P<value>.+)",
+    )?;
+
+    parse(
+        &mut ud,
+        |sink, captures| uv_handler(sink, captures),
+        dir.join("Unihan_Variants.txt"),
+        None,
+        r"^U[+](?P<point>[0-9A-F]+)\t(?P<key>kTraditionalVariant|kSimplifiedVariant|kSemanticVariant|kSpecializedSemanticVariant|kZVariant|kSpoofingVariant)\t(?P<value>.+)",
+    )?;
+
+    let mut radicals = Vec::default();
+
+    parse(
+        &mut radicals,
+        cjkr_handler,
+        dir.join("CJKRadicals.txt"),
+        None,
+        r"^(?P<radical>[0-9]+'*); *(?P<character>[0-9A-F]+)?; *(?P<ideograph>[0-9A-F]+)",
+    )?;
+
+    parse(
+        &mut ud,
+        |sink, captures| ed_handler(sink, captures),
+        dir.join("emoji-data.txt"),
+        None,
+        r"^(?P<first>[0-9A-F]+)(?:[.][.](?P<last>[0-9A-F]+))?\s*;\s*(?P<property>Emoji|Extended_Pictographic|Emoji_Component|Emoji_Presentation|Emoji_Modifier|Emoji_Modifier_Base)(\s|#|$)",
+    )?;
+
+    let mut sequences = Sequences::default();
+
+    parse(
+        &mut ud,
+        |_, captures| {
+            let bits = SequenceBits::NamedSequence;
+            ns_handler(&mut popularity, &mut sequences, bits, captures)
+        },
+        dir.join("NamedSequences.txt"),
+        None,
+        r"^(?P<name>[^#].*)\s*;\s*(?P<points>[0-9A-F]+(?: [0-9A-F]+)*)",
+    )?;
+
+    parse(
+        &mut ud,
+        |_, captures| {
+            let bits = SequenceBits::NamedSequenceProvisional;
+            ns_handler(&mut popularity, &mut sequences, bits, captures)
+        },
+        dir.join("NamedSequencesProv.txt"),
+        None,
+        r"^(?P<name>[^#].*)\s*;\s*(?P<points>[0-9A-F]+(?: [0-9A-F]+)*)",
+    )?;
+
+    let mut emoji_version = None;
+    parse(
+        &mut emoji_version,
+        version_handler,
+        dir.join("emoji-test.txt"),
+        "version",
+        r"^# Version: (?P<version>[0-9.]+)$",
+    )?;
+    let emoji_version = emoji_version.ok_or_eyre("missing version in emoji-test.txt")?;
+
+    let mut et_state = EmojiTestState::default();
+
+    parse(
+        &mut ud,
+        |sink, captures| {
+            et_handler(
+                &mut popularity,
+                &mut et_state,
+                sink,
+                &mut sequences,
+                captures,
+            )
+        },
+        dir.join("emoji-test.txt"),
+        None,
+        r"^# group: (?P<group>.+)|^# subgroup: (?P<subgroup>.+)|^(?P<points>[0-9A-F]+(?: [0-9A-F]+)*)\s*;\s*(?P<status>[a-z-]+)\s*# .* E(?P<version>[0-9]+[.][0-9]+) (?P<name>.+)",
+    )?;
+
+    parse(
+        &mut ud,
+        |_, captures| sv_handler(&mut popularity, &mut sequences, captures),
+        dir.join("StandardizedVariants.txt"),
+        None,
+        r"^(?P<points>[0-9A-F]+(?: [0-9A-F]+)*)\s*;\s*(?P<description>[^;]+?)\s*;\s*(?P<environments>[^;#]*?)\s*;?\s*#",
+    )?;
+
+    parse(
+        &mut ud,
+        |sink, captures| evs_handler(&mut popularity, sink, &mut sequences, captures),
+        dir.join("emoji-variation-sequences.txt"),
+        None,
+        r"^(?P<points>[0-9A-F]+ [0-9A-F]+)\s*;\s*(?P<style>(?:text|emoji) style)\s*;",
+    )?;
+
+    // The Ideographic Variation Database is not part of the UCD, so
+    // these files are only used if a local copy has been provided.
+    let mut ivd_collections = HashMap::default();
+
+    parse_if_exists(
+        &mut ivd_collections,
+        ivdc_handler,
+        dir.join("IVD_Collections.txt"),
+        None,
+        r"^(?P<collection>[^#;]+);(?P<pattern>[^;]+)(;|$)",
+    )?;
+
+    parse_if_exists(
+        &mut ud,
+        |_, captures| ivd_handler(&mut popularity, &ivd_collections, &mut sequences, captures),
+        dir.join("IVD_Sequences.txt"),
+        None,
+        r"^(?P<points>[0-9A-F]+ [0-9A-F]+)\s*;\s*(?P<collection>[^;]+?)\s*;\s*(?P<identifier>[^;\s]+)",
+    )?;
+
+    // CLDR is also optional. To use it, copy or link its common
+    // directory (https://github.com/unicode-org/cldr) to cldr.
+    let mut cldr_territories = BTreeMap::default();
+    let mut cldr_subdivisions = BTreeMap::default();
+
+    parse_if_exists(
+        &mut cldr_territories,
+        cldr_name_handler,
+        dir.join("cldr/main/en.xml"),
+        "territories",
+        r#"^\s*<territory type="(?P<code>[A-Z]{2})">(?P<name>[^<]+)</territory>"#,
+    )?;
+
+    parse_if_exists(
+        &mut cldr_subdivisions,
+        cldr_name_handler,
+        dir.join("cldr/subdivisions/en.xml"),
+        None,
+        r#"^\s*<subdivision type="(?P<code>[a-z]{2}[0-9a-z]{1,4})">(?P<name>[^<]+)</subdivision>"#,
+    )?;
+
+    derive_flag_names(
+        &mut popularity,
+        &cldr_territories,
+        &cldr_subdivisions,
+        &mut sequences,
+    );
+
+    for path in ["cldr/annotations/en.xml", "cldr/annotationsDerived/en.xml"] {
+        parse_if_exists(
+            &mut ud,
+            |sink, captures| annotation_handler(&mut popularity, sink, &mut sequences, captures),
+            dir.join(path),
+            None,
+            r#"^\s*<annotation cp="(?P<cp>[^"]+)">(?P<keywords>[^<]+)</annotation>"#,
+        )?;
+    }
+
+    // Names and keywords in other languages, for each locale in
+    // locale_codes, are written to separate files.
+    let mut locales = Vec::default();
+
+    for &code in locale_codes {
+        let mut locale = Locale::default();
+
+        for path in [
+            format!("cldr/annotations/{}.xml", code),
+            format!("cldr/annotationsDerived/{}.xml", code),
+        ] {
+            parse(
+                &mut locale,
+                |sink, captures| locale_annotation_handler(&ud, &sequences, sink, captures),
+                dir.join(path),
+                None,
+                r#"^\s*<annotation cp="(?P<cp>[^"]+)"(?P<tts> type="tts")?>(?P<text>[^<]+)</annotation>"#,
+            )?;
+        }
+
+        locales.push((code.to_owned(), locale));
+    }
+
+    parse(
+        &mut ud,
+        |sink, captures| gbp_handler(sink, captures),
+        dir.join("GraphemeBreakProperty.txt"),
+        None,
+        r"^(?P<first>[0-9A-F]+)(?:[.][.](?P<last>[0-9A-F]+))?\s*;\s*(?P<value>[^ ]+)",
+    )?;
+
+    Ok(Ucd {
+        unicode_version,
+        emoji_version,
+        ud,
+        sequences,
+        name_rules,
+        popularity,
+        radicals,
+        names_list,
+        locales,
+        unihan_fields: unihan_fields.iter().map(|&x| x.to_owned()).collect(),
+    })
+}
+
+pub(crate) fn points<T: Default>() -> Vec<T> {
+    let mut result = Vec::with_capacity(0x110000);
+    result.resize_with(0x110000, Default::default);
+
+    result
+}
+
+pub fn derived_name(data: &[Details], rules: &NameRules, point: usize) -> Option<String> {
+    for &(first, last, ref rule, ref prefix) in rules {
+        if first <= point && point <= last {
+            return match rule {
+                NameRule::NR1 => {
+                    hangul_syllable_short_name(data, point).map(|x| format!("{}{}", prefix, x))
+                }
+                NameRule::NR2 => Some(format!("{}{:04X}", prefix, point)),
+            };
+        }
+    }
+
+    None
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use color_eyre::eyre;
+use regex::Captures;
+
+use crate::captures::CapturesExt;
+use crate::cldr::{qualified_points, unescape};
+use crate::details::{Alias, AliasType, Details};
+use crate::points;
+use crate::pool::Popularity;
+use crate::sequence::Sequences;
+
+// Localised names and keywords for one CLDR locale, with a string pool
+// of their own so that clients only need to fetch the locales they use.
+pub(crate) struct Locale {
+    pub popularity: Popularity,
+    pub points: Vec<LocaleDetails>,
+    pub sequences: HashMap<Vec<usize>, LocaleDetails>,
+}
+
+#[derive(Debug, Default, Clone)]
+pub(crate) struct LocaleDetails {
+    pub name: Option<Rc<str>>,
+    pub alias: Vec<Alias>,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self {
+            popularity: Popularity::default(),
+            points: points(),
+            sequences: HashMap::default(),
+        }
+    }
+}
+
+pub(crate) fn locale_annotation_handler(
+    ud: &[Details],
+    sequences: &Sequences,
+    sink: &mut Locale,
+    captures: Captures,
+) -> eyre::Result<()> {
+    let cp = unescape(captures.try_name("cp")?);
+    let text = unescape(captures.try_name("text")?);
+    let points = cp.chars().map(|x| x as usize).collect::<Vec<_>>();
+    let points = qualified_points(ud, sequences, points);
+
+    // sequences are only localised if another input defines them
+    if points.len() > 1 && sequences.get(&points).is_none() {
+        return Ok(());
+    }
+
+    // annotationsDerived uses this to mean “inherit from parent locale”
+    if text == "↑↑↑" {
+        return Ok(());
+    }
+
+    let details = if points.len() > 1 {
+        sink.sequences.entry(points).or_default()
+    } else {
+        &mut sink.points[points[0]]
+    };
+
+    if captures.name("tts").is_some() {
+        details.name = Some(sink.popularity.vote(&text));
+        return Ok(());
+    }
+
+    for keyword in text.split('|').map(str::trim) {
+        if details.alias.iter().any(|x| &*x.inner == keyword) {
+            continue;
+        }
+        details.alias.push(Alias {
+            inner: sink.popularity.vote(keyword),
+            r#type: AliasType::CldrKeyword,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::TryInto;
+
+use byteorder::ReadBytesExt;
+use color_eyre::eyre::{self, OptionExt};
+
+// Character names, compressed in the spirit of ICU’s unames: words like
+// LATIN, SMALL and LETTER are stored once in a token dictionary, and
+// each name is a sequence of token indices.
+//
+// Names are split after each space or hyphen, dropping the spaces, and
+// joined with a space after each token that doesn’t end with a hyphen,
+// so “TIBETAN MARK TSA -PHRU” is TIBETAN, MARK, TSA, -, PHRU, and
+// “TIBETAN MARK BKA- SHOG YIG MGO” is TIBETAN, MARK, BKA-, (empty),
+// SHOG, YIG, MGO.
+//
+// tokens: JSON array of strings, most frequent first
+// names: for each distinct name: token count (u8), then each token
+// index in big-endian groups of 7 bits, with the high bit set in every
+// byte but the last (so the 128 most frequent tokens take one byte)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Names {
+    pub tokens: Vec<String>,
+    pub data: Vec<u8>,
+}
+
+impl Names {
+    // Builds a token dictionary for the given names, then encodes each
+    // distinct name once, returning the offset in data of each name.
+    pub fn encode(names: &[&str]) -> (Self, Vec<usize>) {
+        let mut counts = HashMap::<&str, usize>::default();
+        let distinct = names.iter().collect::<HashSet<_>>();
+        for token in distinct.into_iter().flat_map(|x| tokenize(x)) {
+            *counts.entry(token).or_default() += 1;
+        }
+        let mut tokens = counts.into_iter().collect::<Vec<_>>();
+        tokens.sort_by(|(p, m), (q, n)| m.cmp(n).reverse().then_with(|| p.cmp(q)));
+        let indices = tokens
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, _))| (x, i))
+            .collect::<HashMap<_, _>>();
+
+        let mut result = Self {
+            tokens: tokens.iter().map(|&(x, _)| x.to_owned()).collect(),
+            data: vec![],
+        };
+        let mut offsets = HashMap::<&str, usize>::default();
+        let offsets = names
+            .iter()
+            .map(|&name| {
+                *offsets.entry(name).or_insert_with(|| {
+                    let offset = result.data.len();
+                    let tokens = tokenize(name);
+                    let count = tokens.len().try_into().expect("name token count overflow");
+                    result.data.push(count);
+                    for token in tokens {
+                        write_varint(&mut result.data, indices[token]);
+                    }
+                    assert_eq!(
+                        result.decode(offset).ok().as_deref(),
+                        Some(name),
+                        "name does not round-trip"
+                    );
+
+                    offset
+                })
+            })
+            .collect();
+
+        (result, offsets)
+    }
+
+    pub fn decode(&self, offset: usize) -> eyre::Result<String> {
+        let mut source = self
+            .data
+            .get(offset..)
+            .ok_or_eyre("name offset out of bounds")?;
+        let mut result = String::new();
+        let mut previous = None::<&str>;
+
+        for _ in 0..source.read_u8()? {
+            let token = self
+                .tokens
+                .get(read_varint(&mut source)?)
+                .ok_or_eyre("name token out of bounds")?;
+            if previous.is_some_and(|x| !x.ends_with('-')) {
+                result.push(' ');
+            }
+            result.push_str(token);
+            previous = Some(token);
+        }
+
+        Ok(result)
+    }
+}
+
+fn tokenize(name: &str) -> Vec<&str> {
+    name.split_inclusive([' ', '-'])
+        .map(|x| x.strip_suffix(' ').unwrap_or(x))
+        .collect()
+}
+
+fn write_varint(sink: &mut Vec<u8>, value: usize) {
+    let mut shift = (0..).step_by(7).find(|&x| value >> x < 0x80).unwrap();
+    while shift > 0 {
+        sink.push(0x80 | ((value >> shift) & 0x7F) as u8);
+        shift -= 7;
+    }
+    sink.push((value & 0x7F) as u8);
+}
+
+fn read_varint(source: &mut &[u8]) -> eyre::Result<usize> {
+    let mut result = 0usize;
+    loop {
+        let byte = source.read_u8()?;
+        result = (result << 7) | usize::from(byte & 0x7F);
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::rc::Rc;
+
+use color_eyre::eyre::{self, bail};
+use regex::{Captures, Regex};
+
+use crate::captures::CapturesExt;
+use crate::details::{Alias, AliasType, Details, Note, NoteType};
+use crate::pool::Popularity;
+
+// Editorial content from the code charts, with a string pool of its own
+// for notes and subheaders, since most clients never fetch them.
+#[derive(Debug)]
+pub(crate) struct NamesListState {
+    pub popularity: Popularity,
+    pub subheaders: Vec<(usize, usize, Rc<str>)>,
+    point: Option<usize>,
+    subheader: Option<Rc<str>>,
+    range: Option<(usize, usize)>,
+    xref: Regex,
+}
+
+impl Default for NamesListState {
+    fn default() -> Self {
+        Self {
+            popularity: Popularity::default(),
+            subheaders: Vec::default(),
+            point: None,
+            subheader: None,
+            range: None,
+            // either “x 2420” or “x (symbol for null - 2400)”
+            xref: Regex::new(r"(?:^|[ (])(?P<point>[0-9A-F]{4,6})\)?$").unwrap(),
+        }
+    }
+}
+
+impl NamesListState {
+    // Closes the current subheader, if any, at the last point listed under it.
+    pub fn finish_subheader(&mut self) {
+        if let (Some(name), Some((first, last))) = (self.subheader.take(), self.range.take()) {
+            self.subheaders.push((first, last, name));
+        }
+    }
+}
+
+pub(crate) fn nl_handler(
+    popularity: &mut Popularity,
+    state: &mut NamesListState,
+    sink: &mut [Details],
+    captures: Captures,
+) -> eyre::Result<()> {
+    if captures.name("block").is_some() {
+        state.finish_subheader();
+        state.point = None;
+        return Ok(());
+    }
+    if let Some(subheader) = captures.name("subheader") {
+        state.finish_subheader();
+        state.subheader = Some(state.popularity.vote(subheader.as_str()));
+        return Ok(());
+    }
+    if let Some(point) = captures.name("point") {
+        let point = usize::from_str_radix(point.as_str(), 16)?;
+        state.point = Some(point);
+        if state.subheader.is_some() {
+            let first = state.range.map_or(point, |(first, _)| first);
+            state.range = Some((first, point));
+        }
+        return Ok(());
+    }
+
+    // annotations before the first character in a block are block notes
+    let point = match state.point {
+        Some(x) => x,
+        None => return Ok(()),
+    };
+    let value = captures.try_name("value")?;
+
+    match captures.try_name("kind")? {
+        "=" => {
+            // most control code aliases are also in NameAliases.txt
+            if sink[point].alias.iter().any(|x| &*x.inner == value) {
+                return Ok(());
+            }
+            sink[point].alias.push(Alias {
+                inner: popularity.vote(value),
+                r#type: AliasType::Informal,
+            });
+        }
+        "*" => sink[point].nlnote.push(Note {
+            inner: state.popularity.vote(value),
+            r#type: NoteType::Informative,
+        }),
+        "~" => sink[point].nlnote.push(Note {
+            inner: state.popularity.vote(value),
+            r#type: NoteType::Variation,
+        }),
+        "x" => {
+            let xref = match state.xref.captures(value).and_then(|x| x.name("point")) {
+                Some(x) => x.as_str(),
+                None => bail!("bad cross reference in NamesList.txt: {}", &captures[0]),
+            };
+            sink[point].nlxref.push(usize::from_str_radix(xref, 16)?);
+        }
+        _ => unreachable!(),
+    }
+
+    Ok(())
+}
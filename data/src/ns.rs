@@ -3,11 +3,12 @@ use regex::Captures;
 
 use crate::captures::CapturesExt;
 use crate::pool::Popularity;
-use crate::sequence::Sequences;
+use crate::sequence::{SequenceBits, Sequences};
 
 pub(crate) fn ns_handler(
     popularity: &mut Popularity,
     sequences: &mut Sequences,
+    bits: SequenceBits,
     captures: Captures,
 ) -> eyre::Result<()> {
     let points = captures
@@ -17,7 +18,7 @@ pub(crate) fn ns_handler(
         .collect::<Result<Vec<_>, _>>()?;
     let name = captures.try_name("name")?;
 
-    sequences.insert(&points, popularity.vote(name));
+    sequences.insert(&points, popularity.vote(name), bits);
 
     Ok(())
 }
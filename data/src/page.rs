@@ -1,7 +1,12 @@
-#[repr(u8)]
-#[derive(Debug, Clone, Copy)]
-pub(crate) enum PageBits {
-    HasAnyNameExceptNr2 = 1 << 0,
-    HasAnyUhdef = 1 << 1,
-    HasAnyAlias = 1 << 2,
+use crate::schema::schema_enum;
+
+schema_enum! {
+    #[repr(u8)]
+    #[derive(Debug, Clone, Copy)]
+    pub(crate) enum PageBits {
+        HasAnyNameExceptNr2 = 1 << 0,
+        HasAnyUhdef = 1 << 1,
+        HasAnyAlias = 1 << 2,
+        HasAnyUnihanReading = 1 << 3,
+    }
 }
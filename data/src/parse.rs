@@ -1,5 +1,6 @@
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::path::Path;
 
 use color_eyre::eyre;
 use regex::{Captures, Regex};
@@ -7,14 +8,15 @@ use regex::{Captures, Regex};
 pub(crate) fn parse<R, H: FnMut(&mut R, Captures) -> eyre::Result<()>>(
     sink: &mut R,
     mut handler: H,
-    path: &str,
+    path: impl AsRef<Path>,
     label: impl Into<Option<&'static str>>,
     pattern: &str,
 ) -> eyre::Result<()> {
+    let path = path.as_ref();
     if let Some(label) = label.into() {
-        println!("Processing {} ({}) ...", path, label);
+        println!("Processing {} ({}) ...", path.display(), label);
     } else {
-        println!("Processing {} ...", path);
+        println!("Processing {} ...", path.display());
     }
 
     let source = BufReader::new(File::open(path)?);
@@ -28,3 +30,19 @@ pub(crate) fn parse<R, H: FnMut(&mut R, Captures) -> eyre::Result<()>>(
 
     Ok(())
 }
+
+pub(crate) fn parse_if_exists<R, H: FnMut(&mut R, Captures) -> eyre::Result<()>>(
+    sink: &mut R,
+    handler: H,
+    path: impl AsRef<Path>,
+    label: impl Into<Option<&'static str>>,
+    pattern: &str,
+) -> eyre::Result<()> {
+    let path = path.as_ref();
+    if !path.exists() {
+        println!("Skipping {} (not found) ...", path.display());
+        return Ok(());
+    }
+
+    parse(sink, handler, path, label, pattern)
+}
@@ -0,0 +1,89 @@
+// Tone-marked vowels in kMandarin, with their base letter and tone number.
+// ü is spelled v, as in most pinyin input methods (but see pinyin_keys).
+const TONE_MARKS: &[(char, char, usize)] = &[
+    ('ā', 'a', 1),
+    ('á', 'a', 2),
+    ('ǎ', 'a', 3),
+    ('à', 'a', 4),
+    ('ē', 'e', 1),
+    ('é', 'e', 2),
+    ('ě', 'e', 3),
+    ('è', 'e', 4),
+    ('ê', 'e', 0),
+    ('ī', 'i', 1),
+    ('í', 'i', 2),
+    ('ǐ', 'i', 3),
+    ('ì', 'i', 4),
+    ('ō', 'o', 1),
+    ('ó', 'o', 2),
+    ('ǒ', 'o', 3),
+    ('ò', 'o', 4),
+    ('ū', 'u', 1),
+    ('ú', 'u', 2),
+    ('ǔ', 'u', 3),
+    ('ù', 'u', 4),
+    ('ǖ', 'v', 1),
+    ('ǘ', 'v', 2),
+    ('ǚ', 'v', 3),
+    ('ǜ', 'v', 4),
+    ('ü', 'v', 0),
+    ('ḿ', 'm', 2),
+    ('ń', 'n', 2),
+    ('ň', 'n', 3),
+    ('ǹ', 'n', 4),
+];
+
+// Combining marks, for syllables with no precomposed form (e.g. m̄, ê̄).
+const COMBINING_MARKS: &[(char, usize)] = &[
+    ('\u{0304}', 1),
+    ('\u{0301}', 2),
+    ('\u{030C}', 3),
+    ('\u{0300}', 4),
+];
+
+// Returns the search keys for a kMandarin value, which may have more than
+// one reading (e.g. “lán lǎn”), in order and without duplicates.
+pub fn pinyin_keys(value: &str) -> Vec<String> {
+    let mut result = Vec::default();
+
+    for key in value.split_whitespace().flat_map(syllable_keys) {
+        if !result.contains(&key) {
+            result.push(key);
+        }
+    }
+
+    result
+}
+
+// Returns the toneless (lan) and tone-numbered (lan2) search keys for one
+// pinyin syllable. Neutral tone syllables have only the toneless key. ü is
+// spelled both v and u (lǜ is lv4, lv, lu4 and lu), since people without a
+// pinyin input method usually type u.
+fn syllable_keys(syllable: &str) -> Vec<String> {
+    let mut toneless = String::default();
+    let mut tone = 0;
+
+    for c in syllable.to_lowercase().chars() {
+        if let Some(&(_, base, x)) = TONE_MARKS.iter().find(|&&(x, _, _)| x == c) {
+            toneless.push(base);
+            tone = tone.max(x);
+        } else if let Some(&(_, x)) = COMBINING_MARKS.iter().find(|&&(x, _)| x == c) {
+            tone = tone.max(x);
+        } else {
+            toneless.push(c);
+        }
+    }
+
+    let mut spellings = vec![toneless.clone()];
+    if toneless.contains('v') {
+        spellings.push(toneless.replace('v', "u"));
+    }
+
+    spellings
+        .into_iter()
+        .flat_map(|x| match tone {
+            0 => vec![x],
+            tone => vec![format!("{}{}", x, tone), x],
+        })
+        .collect()
+}
@@ -43,6 +43,10 @@ impl Popularity {
 }
 
 impl Pool {
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
     pub fn r#use(&self, string: &str) -> usize {
         self.inner[string]
     }
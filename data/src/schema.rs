@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::container::SectionType;
+use crate::details::{
+    AliasType, Bits, EmojiBits, EmojiStatus, GraphemeBreak, NoteType, VariantType,
+};
+use crate::page::PageBits;
+use crate::sequence::SequenceBits;
+
+// An enum whose variants are written to schema.ts, so that clients
+// read values and bits with the same names and numbers as we write.
+pub(crate) trait SchemaEnum {
+    const NAME: &'static str;
+    const VARIANTS: &'static [(&'static str, u32)];
+}
+
+// Defines an enum with explicit discriminants, and implements
+// SchemaEnum for it from the same list of variants.
+macro_rules! schema_enum {
+    ($(#[$meta:meta])* $vis:vis enum $name:ident { $($variant:ident = $value:expr,)* }) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($variant = $value,)*
+        }
+
+        impl $crate::schema::SchemaEnum for $name {
+            const NAME: &'static str = stringify!($name);
+            const VARIANTS: &'static [(&'static str, u32)] =
+                &[$((stringify!($variant), Self::$variant as u32),)*];
+        }
+    };
+}
+pub(crate) use schema_enum;
+
+// Generates schema.ts, with the enums and bit positions used in the
+// output files, and the type of the elements in each file.
+pub(crate) fn generate_schema(files: &BTreeMap<String, SectionType>) -> String {
+    let mut result = String::new();
+    writeln!(result, "// generated by data/src/schema.rs, do not edit").unwrap();
+
+    values::<SectionType>(&mut result);
+    values::<AliasType>(&mut result);
+    values::<NoteType>(&mut result);
+    values::<VariantType>(&mut result);
+    values::<EmojiStatus>(&mut result);
+    values::<GraphemeBreak>(&mut result);
+
+    // bit positions (not masks) in data.bits.bin, data.ebits.bin,
+    // data.pagebits.bin and data.seqk.bin
+    bits::<Bits>(&mut result);
+    bits::<EmojiBits>(&mut result);
+    bits::<PageBits>(&mut result);
+    bits::<SequenceBits>(&mut result);
+
+    writeln!(result).unwrap();
+    writeln!(result, "export const FILES = {{").unwrap();
+    for (path, r#type) in files {
+        writeln!(result, "  {:?}: SectionType.{:?},", path, r#type).unwrap();
+    }
+    writeln!(result, "}} as const;").unwrap();
+    writeln!(result).unwrap();
+    writeln!(result, "export type DataFile = keyof typeof FILES;").unwrap();
+
+    result
+}
+
+fn values<T: SchemaEnum>(sink: &mut String) {
+    write_enum(sink, T::NAME, T::VARIANTS.iter().copied());
+}
+
+fn bits<T: SchemaEnum>(sink: &mut String) {
+    let variants = T::VARIANTS.iter().map(|&(name, value)| {
+        assert!(
+            value.is_power_of_two(),
+            "{}::{} is not a bit",
+            T::NAME,
+            name
+        );
+
+        (name, value.trailing_zeros())
+    });
+    write_enum(sink, T::NAME, variants);
+}
+
+fn write_enum<'v>(sink: &mut String, name: &str, variants: impl Iterator<Item = (&'v str, u32)>) {
+    writeln!(sink).unwrap();
+    writeln!(sink, "export enum {} {{", name).unwrap();
+    for (variant, value) in variants {
+        writeln!(sink, "  {} = {},", variant, value).unwrap();
+    }
+    writeln!(sink, "}}").unwrap();
+}
@@ -1,4 +1,12 @@
-use std::{collections::BTreeMap, rc::Rc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    rc::Rc,
+};
+
+use enumflags2::{bitflags, BitFlags};
+
+use crate::details::{Alias, EmojiTest};
+use crate::schema::schema_enum;
 
 #[derive(Debug, Default)]
 pub struct Sequences {
@@ -9,25 +17,91 @@ pub type SequenceKey = (usize, usize);
 
 #[derive(Debug)]
 pub struct Sequence {
+    pub bits: BitFlags<SequenceBits>,
     pub points: Vec<usize>,
     pub names: Vec<Rc<str>>,
+    // whether each of names is provisional (NamedSequencesProv.txt)
+    pub provisional: Vec<bool>,
+    pub alias: Vec<Alias>,
+    pub etest: Option<EmojiTest>,
+}
+
+schema_enum! {
+    #[repr(u8)]
+    #[derive(Debug, Clone, Copy)]
+    #[bitflags]
+    pub enum SequenceBits {
+        NamedSequence = 1 << 0,
+        EmojiTest = 1 << 1,
+        StandardizedVariant = 1 << 2,
+        EmojiVariation = 1 << 3,
+        Ivd = 1 << 4,
+        NamedSequenceProvisional = 1 << 5,
+        DerivedFlag = 1 << 6,
+    }
 }
 
 impl Sequences {
-    pub fn insert(&mut self, points: &[usize], name: Rc<str>) {
+    pub fn insert(&mut self, points: &[usize], name: Rc<str>, bits: SequenceBits) {
+        let sequence = self.entry(points);
+        sequence.names.push(name);
+        sequence
+            .provisional
+            .push(matches!(bits, SequenceBits::NamedSequenceProvisional));
+        sequence.bits |= bits;
+    }
+
+    pub fn entry(&mut self, points: &[usize]) -> &mut Sequence {
         let bucket = self.buckets.entry(key(points)).or_default();
 
-        for sequence in bucket.iter_mut() {
-            if sequence.points == points {
-                sequence.names.push(name);
-                return;
-            }
+        if let Some(i) = bucket.iter().position(|x| x.points == points) {
+            return &mut bucket[i];
         }
 
         bucket.push(Sequence {
+            bits: BitFlags::empty(),
             points: points.to_owned(),
-            names: vec![name],
-        })
+            names: vec![],
+            provisional: vec![],
+            alias: vec![],
+            etest: None,
+        });
+
+        bucket.last_mut().expect("just pushed")
+    }
+
+    // Returns the sequence with the given points, if any, without
+    // inserting one like entry.
+    pub fn get(&self, points: &[usize]) -> Option<&Sequence> {
+        self.buckets
+            .get(&key(points))?
+            .iter()
+            .find(|x| x.points == points)
+    }
+
+    pub fn get_mut(&mut self, points: &[usize]) -> Option<&mut Sequence> {
+        self.buckets
+            .get_mut(&key(points))?
+            .iter_mut()
+            .find(|x| x.points == points)
+    }
+
+    // Returns the index of each sequence in output order.
+    pub fn indices(&self) -> HashMap<&[usize], usize> {
+        self.buckets
+            .values()
+            .flatten()
+            .enumerate()
+            .map(|(i, x)| (&*x.points, i))
+            .collect()
+    }
+}
+
+impl Sequence {
+    // Adds a name that is not provisional.
+    pub fn push_name(&mut self, name: Rc<str>) {
+        self.names.push(name);
+        self.provisional.push(false);
     }
 }
 
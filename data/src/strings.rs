@@ -0,0 +1,50 @@
+use std::convert::TryInto;
+use std::io::Write;
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use color_eyre::eyre::{self, ensure, OptionExt};
+
+// Binary string pools, which clients can index into without parsing
+// JSON or decoding every string up front.
+//
+// count (u32), then count + 1 offsets (u32) into the UTF-8 that follows,
+// where string i is the bytes from offset i up to offset i + 1
+pub fn write_strings<S: AsRef<str>>(sink: &mut Vec<u8>, strings: &[S]) -> eyre::Result<()> {
+    let count = strings.len().try_into().expect("string count overflow");
+    sink.write_u32::<BigEndian>(count)?;
+
+    let mut offset = 0u32;
+    sink.write_u32::<BigEndian>(offset)?;
+    for string in strings {
+        let len = string.as_ref().len().try_into().unwrap();
+        offset = offset.checked_add(len).expect("string pool overflow");
+        sink.write_u32::<BigEndian>(offset)?;
+    }
+
+    for string in strings {
+        sink.write_all(string.as_ref().as_bytes())?;
+    }
+
+    Ok(())
+}
+
+pub fn read_strings(bytes: &[u8]) -> eyre::Result<Vec<String>> {
+    let mut source = bytes;
+    let count = source.read_u32::<BigEndian>()?;
+    let offsets = (0..=count)
+        .map(|_| Ok(source.read_u32::<BigEndian>()? as usize))
+        .collect::<eyre::Result<Vec<_>>>()?;
+    ensure!(
+        offsets.last() == Some(&source.len()),
+        "string pool length mismatch"
+    );
+
+    offsets
+        .windows(2)
+        .map(|x| {
+            let string = source.get(x[0]..x[1]).ok_or_eyre("string out of bounds")?;
+
+            Ok(String::from_utf8(string.to_owned())?)
+        })
+        .collect()
+}
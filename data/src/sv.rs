@@ -0,0 +1,36 @@
+use color_eyre::eyre;
+use regex::Captures;
+
+use crate::captures::CapturesExt;
+use crate::pool::Popularity;
+use crate::sequence::{SequenceBits, Sequences};
+
+pub(crate) fn sv_handler(
+    popularity: &mut Popularity,
+    sequences: &mut Sequences,
+    captures: Captures,
+) -> eyre::Result<()> {
+    let points = captures
+        .try_name("points")?
+        .split(" ")
+        .map(|x| usize::from_str_radix(x, 16))
+        .collect::<Result<Vec<_>, _>>()?;
+    let description = captures.try_name("description")?;
+    let environments = captures.name_or("environments", "");
+
+    // Some variants only differ in particular shaping environments
+    // (isolate, initial, medial, final), mostly for Mongolian.
+    let name = if environments.is_empty() {
+        description.to_owned()
+    } else {
+        format!("{} ({})", description, environments)
+    };
+
+    sequences.insert(
+        &points,
+        popularity.vote(&name),
+        SequenceBits::StandardizedVariant,
+    );
+
+    Ok(())
+}
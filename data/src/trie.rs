@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+
+// Multi-stage code point tries, in the spirit of ICU’s CodePointTrie.
+//
+// The high bits of a point index the first stage, whose entries are
+// block numbers in the next stage (an index stage, for three-stage
+// tries), and so on down to blocks of values in the data stage.
+// Identical blocks in each stage are stored once, and blocks where no
+// point has a value are not stored at all (block number 0xFFFF).
+//
+// A trie covers all 0x110000 points, or with chunked output, the points
+// in one chunk (relative to its first point), which the first stage
+// has an entry for every 2^(index bits + data bits) of.
+//
+// header: value width in bytes (u8), stage count (u8),
+// index stage bits (u8, 0 for two stages), data stage bits (u8),
+// index block count (u16), data block count (u16)
+// then the first stage, the index stage and the data stage, as u16
+// block numbers except for the values in the data stage
+pub(crate) const HEADER_LEN: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TrieLayout {
+    pub index_bits: u8,
+    pub data_bits: u8,
+}
+
+#[derive(Debug)]
+pub(crate) struct Trie<U> {
+    pub layout: TrieLayout,
+    pub first: Vec<u16>,
+    pub index: Vec<u16>,
+    pub data: Vec<U>,
+    // Number of data blocks with values, before deduplication.
+    pub blocks: usize,
+}
+
+impl TrieLayout {
+    // Every layout we consider, simplest first. The lowest stages must
+    // cover at most 2^12 points, so that 0x110000 points and chunks of
+    // whole 16-page ranges divide evenly into first-stage entries.
+    pub fn candidates() -> impl Iterator<Item = Self> {
+        let two = (4..=8).map(|data_bits| Self {
+            index_bits: 0,
+            data_bits,
+        });
+        let three = (3..=6).flat_map(|index_bits| {
+            (3..=6).map(move |data_bits| Self {
+                index_bits,
+                data_bits,
+            })
+        });
+
+        two.chain(three)
+    }
+
+    fn stages(&self) -> u8 {
+        if self.index_bits > 0 {
+            3
+        } else {
+            2
+        }
+    }
+}
+
+impl Display for TrieLayout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let first_bits = 21 - self.index_bits - self.data_bits;
+        if self.index_bits > 0 {
+            write!(f, "{}/{}/{}", first_bits, self.index_bits, self.data_bits)
+        } else {
+            write!(f, "{}/{}", first_bits, self.data_bits)
+        }
+    }
+}
+
+impl<U: Copy + Eq + Hash> Trie<U> {
+    // Builds the smallest trie for the given values, one per point.
+    pub fn smallest(values: &[Option<U>], default: U, width: usize) -> Self {
+        assert_eq!(values.len() % 0x1000, 0);
+
+        // present[i] is the number of values before point i, so that
+        // we can skip empty blocks without looking inside them
+        let mut present = Vec::with_capacity(values.len() + 1);
+        present.push(0);
+        for value in values {
+            present.push(present.last().unwrap() + value.is_some() as usize);
+        }
+
+        TrieLayout::candidates()
+            .filter_map(|layout| Self::build(values, &present, default, layout))
+            .reduce(|a, x| if x.len(width) < a.len(width) { x } else { a })
+            .expect("no trie layout fits")
+    }
+
+    // Returns None if there are too many blocks for u16 block numbers.
+    fn build(
+        values: &[Option<U>],
+        present: &[usize],
+        default: U,
+        layout: TrieLayout,
+    ) -> Option<Self> {
+        let data_len = 1 << layout.data_bits;
+        let mut data = Vec::default();
+        let mut data_blocks = HashMap::<Vec<U>, u16>::default();
+        let mut data_numbers = Vec::default();
+
+        for start in (0..values.len()).step_by(data_len) {
+            if present[start + data_len] == present[start] {
+                data_numbers.push(0xFFFF);
+                continue;
+            }
+            let block = values[start..][..data_len]
+                .iter()
+                .map(|x| x.unwrap_or(default))
+                .collect::<Vec<_>>();
+            let next = data_blocks.len().try_into().ok().filter(|&x| x < 0xFFFF)?;
+            let number = *data_blocks.entry(block.clone()).or_insert_with(|| {
+                data.extend(block);
+                next
+            });
+            data_numbers.push(number);
+        }
+
+        let blocks = data_numbers.iter().filter(|&&x| x != 0xFFFF).count();
+        if layout.index_bits == 0 {
+            return Some(Self {
+                layout,
+                first: data_numbers,
+                index: vec![],
+                data,
+                blocks,
+            });
+        }
+
+        let index_len = 1 << layout.index_bits;
+        let mut first = Vec::default();
+        let mut index = Vec::default();
+        let mut index_blocks = HashMap::<&[u16], u16>::default();
+
+        for block in data_numbers.chunks(index_len) {
+            if block.iter().all(|&x| x == 0xFFFF) {
+                first.push(0xFFFF);
+                continue;
+            }
+            let next = index_blocks.len().try_into().ok().filter(|&x| x < 0xFFFF)?;
+            let number = *index_blocks.entry(block).or_insert_with(|| {
+                index.extend_from_slice(block);
+                next
+            });
+            first.push(number);
+        }
+
+        Some(Self {
+            layout,
+            first,
+            index,
+            data,
+            blocks,
+        })
+    }
+
+    // Size in bytes, including the header.
+    pub fn len(&self, width: usize) -> usize {
+        HEADER_LEN + (self.first.len() + self.index.len()) * 2 + self.data.len() * width
+    }
+
+    pub fn header(&self, width: usize) -> [u8; HEADER_LEN] {
+        let index_blocks = (self.index.len() >> self.layout.index_bits) as u16;
+        let data_blocks = (self.data.len() >> self.layout.data_bits) as u16;
+        let [a, b] = index_blocks.to_be_bytes();
+        let [c, d] = data_blocks.to_be_bytes();
+
+        [
+            width.try_into().unwrap(),
+            self.layout.stages(),
+            self.layout.index_bits,
+            self.layout.data_bits,
+            a,
+            b,
+            c,
+            d,
+        ]
+    }
+}
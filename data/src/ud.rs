@@ -81,7 +81,7 @@ pub(crate) fn ud_range_handler(
 }
 
 pub(crate) fn process_ud_ranges(
-    ranges: HashMap<String, (usize, Option<usize>)>,
+    ranges: &HashMap<String, (usize, Option<usize>)>,
 ) -> HashMap<usize, usize> {
     assert_eq!(
         ranges
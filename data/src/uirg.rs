@@ -0,0 +1,48 @@
+use color_eyre::eyre::{self, bail};
+use regex::Captures;
+
+use crate::captures::CapturesExt;
+use crate::details::Details;
+
+// Added to residual stroke counts in data.uhrs.bin and data.rsindex.bin,
+// since UAX #38 allows them to be negative.
+pub const RESIDUAL_OFFSET: isize = 8;
+
+pub(crate) fn uirg_handler(sink: &mut [Details], captures: Captures) -> eyre::Result<()> {
+    let point = usize::from_str_radix(captures.try_name("point")?, 16)?;
+    let key = captures.try_name("key")?;
+    // Both fields can have more than one value, but the first value is
+    // the one to use for radical-stroke ordering.
+    let value = captures
+        .try_name("value")?
+        .split(' ')
+        .next()
+        .expect("split always yields at least once");
+
+    match key {
+        "kRSUnicode" => {
+            // radical, then one apostrophe per level of simplification,
+            // then a dot and the residual stroke count, which can be
+            // negative
+            let (radical, residual) = value
+                .split_once('.')
+                .ok_or_else(|| eyre::eyre!("bad kRSUnicode: {value}"))?;
+            let simplified = radical.chars().filter(|&x| x == '\'').count();
+            let radical: usize = radical.trim_end_matches('\'').parse()?;
+            let residual: isize = residual.parse()?;
+            if !(1..=214).contains(&radical)
+                || simplified > 3
+                || !(-RESIDUAL_OFFSET..(1 << 6) - RESIDUAL_OFFSET).contains(&residual)
+            {
+                bail!("kRSUnicode out of range: {value}");
+            }
+            sink[point].uhrs = Some((radical, simplified, residual));
+        }
+        "kTotalStrokes" => {
+            sink[point].uhts = Some(value.parse()?);
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
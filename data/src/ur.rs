@@ -5,6 +5,44 @@ use crate::captures::CapturesExt;
 use crate::details::{Bits, Details};
 use crate::pool::Popularity;
 
+// Unihan_Readings.txt fields to keep by default ($UNIHAN_FIELDS in
+// main.rs). kHanyuPinyin and kTGHZ2013 are also in this file, but their
+// values are mostly unique to each character, so they would use up
+// most of the remaining space in the string pool.
+pub const DEFAULT_UNIHAN_FIELDS: [&str; 9] = [
+    "kDefinition",
+    "kMandarin",
+    "kCantonese",
+    "kJapanese",
+    "kJapaneseOn",
+    "kJapaneseKun",
+    "kKorean",
+    "kHangul",
+    "kVietnamese",
+];
+
+// Data file names for the fields that clients know about.
+const UNIHAN_FILES: [(&str, &str); 9] = [
+    ("kDefinition", "uhdef"),
+    ("kMandarin", "uhman"),
+    ("kCantonese", "uhcan"),
+    ("kJapanese", "uhjpn"),
+    ("kJapaneseOn", "uhjon"),
+    ("kJapaneseKun", "uhjkun"),
+    ("kKorean", "uhkor"),
+    ("kHangul", "uhhang"),
+    ("kVietnamese", "uhviet"),
+];
+
+// Returns the name of the data file for a Unihan field, like uhman for
+// kMandarin, or uhhanyupinyin for kHanyuPinyin.
+pub(crate) fn unihan_file(key: &str) -> String {
+    UNIHAN_FILES.iter().find(|&&(x, _)| x == key).map_or_else(
+        || format!("uh{}", key.trim_start_matches('k').to_lowercase()),
+        |&(_, file)| file.to_owned(),
+    )
+}
+
 pub(crate) fn ur_handler(
     popularity: &mut Popularity,
     sink: &mut [Details],
@@ -14,17 +52,14 @@ pub(crate) fn ur_handler(
     let key = captures.try_name("key")?;
     let value = captures.try_name("value")?;
 
-    match key {
-        "kDefinition" => {
-            assert!(sink[point].name.is_none());
-            sink[point].uhdef = Some(popularity.vote(value));
-            sink[point].bits |= Bits::KdefinitionExists;
-        }
-        "kMandarin" => {
-            sink[point].uhman = Some(popularity.vote(value));
-        }
-        _ => {}
+    if key == "kDefinition" {
+        assert!(sink[point].name.is_none());
+        sink[point].bits |= Bits::KdefinitionExists;
     }
 
+    sink[point]
+        .unihan
+        .insert(key.into(), popularity.vote(value));
+
     Ok(())
 }
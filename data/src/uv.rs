@@ -0,0 +1,25 @@
+use color_eyre::eyre::{self, OptionExt};
+use regex::Captures;
+
+use crate::captures::CapturesExt;
+use crate::details::{Details, Variant, VariantType};
+
+pub(crate) fn uv_handler(sink: &mut [Details], captures: Captures) -> eyre::Result<()> {
+    let point = usize::from_str_radix(captures.try_name("point")?, 16)?;
+    let r#type: VariantType = captures.try_name("key")?.parse()?;
+
+    // each value is U+XXXX, optionally followed by <sources
+    for value in captures.try_name("value")?.split(' ') {
+        let variant = value
+            .strip_prefix("U+")
+            .and_then(|x| x.split('<').next())
+            .ok_or_eyre("bad Unihan variant value")?;
+
+        sink[point].variant.push(Variant {
+            point: usize::from_str_radix(variant, 16)?,
+            r#type,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use color_eyre::eyre;
+use regex::Captures;
+
+use crate::captures::CapturesExt;
+
+pub(crate) fn version_handler(sink: &mut Option<String>, captures: Captures) -> eyre::Result<()> {
+    *sink = Some(captures.try_name("version")?.to_owned());
+
+    Ok(())
+}
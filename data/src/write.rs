@@ -0,0 +1,1173 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryInto;
+use std::fmt::Debug;
+use std::fs::{self, File};
+use std::hash::Hash;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use byteorder::{BigEndian, WriteBytesExt};
+use color_eyre::eyre::{self, ensure, OptionExt};
+use serde::Serialize;
+
+use crate::cjkr::Radical;
+use crate::container::{crc32, Container, Section, SectionType};
+use crate::details::{Alias, Bits, Details, EmojiTest};
+use crate::locale::Locale;
+use crate::names::Names;
+use crate::nl::NamesListState;
+use crate::page::PageBits;
+use crate::pinyin::pinyin_keys;
+use crate::pool::Pool;
+use crate::schema::generate_schema;
+use crate::sequence::{Sequence, Sequences};
+use crate::strings::write_strings;
+use crate::trie::{Trie, TrieLayout};
+use crate::uax29::generate_egcbreak;
+use crate::uirg::RESIDUAL_OFFSET;
+use crate::ur::unihan_file;
+use crate::{OptionRcExt, Ucd};
+
+// Writes the output files to dir.
+pub fn write_all(ucd: Ucd, dir: &Path) -> eyre::Result<()> {
+    write_outputs(ucd, &mut Output::new(Target::Dir(dir)))?;
+
+    // remove any manifest from an earlier write_chunked, so that the
+    // client loads these files rather than the old chunks
+    let manifest = dir.join("data.manifest.json");
+    if manifest.exists() {
+        fs::remove_file(manifest)?;
+    }
+
+    Ok(())
+}
+
+// Writes the output files to dir, splitting each sparse file into
+// chunks of chunk_pages 256-point pages (256 for one chunk per plane),
+// and writes a manifest of the chunks and the hash of each file, which
+// the client reads to fetch each chunk when first used (see src/fetch.ts).
+pub fn write_chunked(ucd: Ucd, dir: &Path, chunk_pages: usize) -> eyre::Result<()> {
+    ensure!(
+        chunk_pages > 0 && chunk_pages % 16 == 0,
+        "chunk size must be a positive multiple of 16 pages"
+    );
+    let mut out = Output::new(Target::Dir(dir));
+    out.chunk_pages = Some(chunk_pages);
+    out.chunks = vec![BTreeMap::default(); 0x1100usize.div_ceil(chunk_pages)];
+
+    write_outputs(ucd, &mut out)
+}
+
+// Writes the output files as sections of a single container at path.
+pub fn write_container(ucd: Ucd, path: &Path) -> eyre::Result<()> {
+    let mut container = Container {
+        unicode_version: ucd.unicode_version.clone(),
+        emoji_version: ucd.emoji_version.clone(),
+        sections: vec![],
+    };
+    write_outputs(ucd, &mut Output::new(Target::Container(&mut container)))?;
+
+    println!("Writing {} ...", path.display());
+    container.write(BufWriter::new(File::create(path)?))?;
+
+    Ok(())
+}
+
+struct Output<'o> {
+    target: Target<'o>,
+    sparse_sizes: Vec<SparseSize>,
+    index_widths: BTreeMap<String, usize>,
+    // size of each chunk in pages, if sparse files are split into chunks
+    chunk_pages: Option<usize>,
+    // for each chunk: the path of each sparse file’s chunk, if any
+    chunks: Vec<BTreeMap<String, String>>,
+    // CRC-32 of each file written so far
+    hashes: BTreeMap<String, u32>,
+    // type of each file written so far, for schema.ts (sparse files
+    // are listed once, even if split into chunks)
+    types: BTreeMap<String, SectionType>,
+}
+
+// Where each output file goes: a loose file in a directory, or a
+// section of a container.
+enum Target<'o> {
+    Dir(&'o Path),
+    Container(&'o mut Container),
+}
+
+// Size of a sparse file as a trie, compared to the same file with a
+// table of every 256-point page and a copy of each non-empty page.
+struct SparseSize {
+    path: String,
+    layout: TrieLayout,
+    paged: usize,
+    trie: usize,
+    blocks: usize,
+    stored_blocks: usize,
+}
+
+impl<'o> Output<'o> {
+    fn new(target: Target<'o>) -> Self {
+        Self {
+            target,
+            sparse_sizes: vec![],
+            index_widths: BTreeMap::default(),
+            chunk_pages: None,
+            chunks: vec![],
+            hashes: BTreeMap::default(),
+            types: BTreeMap::default(),
+        }
+    }
+
+    // Chooses the width of the indices in the flat or record file at
+    // path, which clients find in data.info.json (sparse files record
+    // their width in their own header).
+    fn index_width(&mut self, path: &str, len: usize) -> usize {
+        let result = index_width(len);
+        self.index_widths.insert(path.to_owned(), result);
+
+        result
+    }
+
+    fn report_sparse_sizes(&self) {
+        println!("Sparse files, 256-point pages → tries with block dedup:");
+        let (mut paged, mut trie) = (0, 0);
+        for size in &self.sparse_sizes {
+            println!(
+                "    {:24} {:>8} → {:>8} bytes ({:>5.1}%), {:8} trie, {} of {} data blocks stored",
+                size.path,
+                size.paged,
+                size.trie,
+                percent_saved(size.paged, size.trie),
+                size.layout.to_string(),
+                size.stored_blocks,
+                size.blocks
+            );
+            paged += size.paged;
+            trie += size.trie;
+        }
+        println!(
+            "    {:24} {:>8} → {:>8} bytes ({:>5.1}%)",
+            "total",
+            paged,
+            trie,
+            percent_saved(paged, trie)
+        );
+    }
+}
+
+// Width in bytes of indices into a table of len items: the narrowest of
+// u16, u24 and u32 that leaves the all-ones value free to mean none.
+fn index_width(len: usize) -> usize {
+    (2..=4)
+        .find(|&width| len < no_index(width) as usize)
+        .expect("index overflow")
+}
+
+fn no_index(width: usize) -> u32 {
+    ((1u64 << (8 * width)) - 1) as u32
+}
+
+fn percent_saved(before: usize, after: usize) -> f64 {
+    100.0 * (before as f64 - after as f64) / before as f64
+}
+
+fn write_outputs(ucd: Ucd, out: &mut Output) -> eyre::Result<()> {
+    let Ucd {
+        mut ud,
+        sequences,
+        popularity,
+        radicals,
+        names_list,
+        locales,
+        unihan_fields,
+        ..
+    } = ucd;
+
+    write(out, "egcbreak.ts", SectionType::Text, |sink| {
+        Ok(writeln!(
+            sink,
+            "export const EGCBREAK = /{}/g;",
+            generate_egcbreak()?
+        )?)
+    })?;
+
+    // names are compressed separately (see names.rs), so take them out
+    // before reporting, or the pool would keep them too
+    let name = ud
+        .iter_mut()
+        .map(|x| x.name.take().map(|x| x.to_string()))
+        .collect::<Vec<_>>();
+    write_name_files(out, &name)?;
+
+    let report = popularity.report();
+
+    write(out, "data.string.bin", SectionType::Strings, |sink| {
+        write_strings(sink, &report)
+    })?;
+
+    let pool = Pool::from(&report);
+
+    write_pool_indices(out, &ud, &pool, "data.dnrp.bin", |x| x.dnrp.map_clone())?;
+    write_pool_indices(out, &ud, &pool, "data.gc.bin", |x| x.gc.map_clone())?;
+    write_pool_indices(out, &ud, &pool, "data.block.bin", |x| x.block.map_clone())?;
+    write_pool_indices(out, &ud, &pool, "data.age.bin", |x| x.age.map_clone())?;
+    write_pool_indices(out, &ud, &pool, "data.hjsn.bin", |x| x.hjsn.map_clone())?;
+    for key in &unihan_fields {
+        let path = format!("data.{}.bin", unihan_file(key));
+        write_pool_indices(out, &ud, &pool, &path, |x| x.unihan.get(&**key).cloned())?;
+    }
+    write_sparse(out, &ud, "data.bits.bin", 1, 0, |x| {
+        if !x.bits.is_empty() {
+            Some(x.bits.bits())
+        } else {
+            None
+        }
+    })?;
+    write_sparse(out, &ud, "data.ebits.bin", 1, 0, |x| {
+        if !x.ebits.is_empty() {
+            Some(x.ebits.bits())
+        } else {
+            None
+        }
+    })?;
+    write_sparse(out, &ud, "data.hlvt.bin", 2, 0, |x| {
+        x.hlvt.map(|(l, v, t)| {
+            assert!(l < (1 << 5) && v < (1 << 5) && t < (1 << 5));
+            ((1 << 15) | (l << 10) | (v << 5) | t) as u16
+        })
+    })?;
+    write_sparse(out, &ud, "data.gb.bin", 1, 0, |x| x.gb.map(|x| x as u8))?;
+    let alias = ud.iter().map(|x| &*x.alias).collect::<Vec<_>>();
+    write_alias_files(out, &alias, &pool, "data.")?;
+    write_radical_stroke_files(out, &ud, &radicals)?;
+    write_variant_files(out, &ud)?;
+    write_pinyin_files(out, &ud)?;
+    write_names_list_files(out, &ud, names_list)?;
+    write_emoji_test_files(out, &ud, &sequences, &pool)?;
+    write_sequence_files(out, &sequences, &pool)?;
+    for (code, locale) in locales {
+        write_locale_files(out, &code, locale, &sequences)?;
+    }
+    write(out, "data.pagebits.bin", SectionType::U8, |sink| {
+        for (page, name) in ud.chunks(256).zip(name.chunks(256)) {
+            let mut value = 0;
+            if page
+                .iter()
+                .zip(name)
+                .filter(|(x, name)| name.is_some() || x.bits.contains(Bits::DerivedNameNr1))
+                .count()
+                > 0
+            {
+                value |= PageBits::HasAnyNameExceptNr2 as u8;
+            }
+            if page
+                .iter()
+                .filter(|x| x.unihan.contains_key("kDefinition"))
+                .count()
+                > 0
+            {
+                value |= PageBits::HasAnyUhdef as u8;
+            }
+            if page.iter().filter(|x| !x.alias.is_empty()).count() > 0 {
+                value |= PageBits::HasAnyAlias as u8;
+            }
+            if page
+                .iter()
+                .filter(|x| x.unihan.keys().any(|x| &**x != "kDefinition"))
+                .count()
+                > 0
+            {
+                value |= PageBits::HasAnyUnihanReading as u8;
+            }
+            sink.write_u8(value)?;
+        }
+
+        Ok(())
+    })?;
+    let index_widths = out.index_widths.clone();
+    write(out, "data.info.json", SectionType::Json, |sink| {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct DataInfo {
+            sequence_bucket_count: usize,
+            sequence_count: usize,
+            radical_stroke_group_count: usize,
+            index_widths: BTreeMap<String, usize>,
+            // Unihan_Readings.txt fields, each written to data.<file>.bin
+            unihan_fields: Vec<UnihanField>,
+        }
+
+        #[derive(Serialize)]
+        struct UnihanField {
+            key: String,
+            file: String,
+        }
+
+        write!(
+            sink,
+            "{}",
+            serde_json::to_string(&DataInfo {
+                sequence_bucket_count: sequences.buckets.len(),
+                sequence_count: sequences.buckets.values().fold(0, |a, x| a + x.len()),
+                radical_stroke_group_count: radical_stroke_groups(&ud).len(),
+                index_widths,
+                unihan_fields: unihan_fields
+                    .iter()
+                    .map(|key| UnihanField {
+                        key: key.clone(),
+                        file: unihan_file(key),
+                    })
+                    .collect(),
+            })?
+        )?;
+
+        Ok(())
+    })?;
+
+    let types = out.types.clone();
+    write(out, "schema.ts", SectionType::Text, |sink| {
+        Ok(write!(sink, "{}", generate_schema(&types))?)
+    })?;
+
+    if let Some(chunk_pages) = out.chunk_pages {
+        write_manifest(out, chunk_pages)?;
+    }
+
+    out.report_sparse_sizes();
+
+    Ok(())
+}
+
+// Lists the files that are loaded up front, then for each chunk, the
+// range of points it covers and its part of each sparse file, so that
+// clients can fetch chunks as needed and cache files by hash.
+fn write_manifest(out: &mut Output, chunk_pages: usize) -> eyre::Result<()> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Manifest {
+        chunk_pages: usize,
+        files: BTreeMap<String, String>,
+        chunks: Vec<Chunk>,
+    }
+    #[derive(Serialize)]
+    struct Chunk {
+        first: usize,
+        last: usize,
+        files: BTreeMap<String, ChunkFile>,
+    }
+    #[derive(Serialize)]
+    struct ChunkFile {
+        path: String,
+        hash: String,
+    }
+
+    let hash = |path: &str| format!("{:08x}", out.hashes[path]);
+    let chunks = out
+        .chunks
+        .iter()
+        .enumerate()
+        .map(|(i, files)| Chunk {
+            first: i * chunk_pages * 256,
+            last: ((i + 1) * chunk_pages * 256).min(0x110000) - 1,
+            files: files
+                .iter()
+                .map(|(file, path)| {
+                    let path = path.clone();
+                    (
+                        file.clone(),
+                        ChunkFile {
+                            hash: hash(&path),
+                            path,
+                        },
+                    )
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    let chunk_paths = out
+        .chunks
+        .iter()
+        .flat_map(|x| x.values())
+        .collect::<BTreeSet<_>>();
+    let files = out
+        .hashes
+        .keys()
+        .filter(|x| !chunk_paths.contains(x))
+        .map(|x| (x.clone(), hash(x)))
+        .collect();
+    let manifest = serde_json::to_string(&Manifest {
+        chunk_pages,
+        files,
+        chunks,
+    })?;
+
+    write(out, "data.manifest.json", SectionType::Json, |sink| {
+        write!(sink, "{}", manifest)?;
+
+        Ok(())
+    })
+}
+
+fn write<W: FnOnce(&mut Vec<u8>) -> eyre::Result<()>>(
+    out: &mut Output,
+    path: &str,
+    r#type: SectionType,
+    writer: W,
+) -> eyre::Result<()> {
+    let mut sink = Vec::default();
+    writer(&mut sink)?;
+    out.hashes.insert(path.to_owned(), crc32(&sink));
+    out.types.insert(path.to_owned(), r#type);
+
+    match &mut out.target {
+        Target::Dir(dir) => {
+            println!("Writing {} ...", path);
+            fs::write(dir.join(path), sink)?;
+        }
+        Target::Container(container) => {
+            println!("Packing {} ...", path);
+            container.sections.push(Section {
+                name: path.to_owned(),
+                r#type,
+                data: sink,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn u8_writer(sink: &mut Vec<u8>, x: u8) -> eyre::Result<()> {
+    sink.write_u8(x)?;
+
+    Ok(())
+}
+
+fn u16_writer(sink: &mut Vec<u8>, x: u16) -> eyre::Result<()> {
+    sink.write_u16::<BigEndian>(x)?;
+
+    Ok(())
+}
+
+fn u32_writer(sink: &mut Vec<u8>, x: u32) -> eyre::Result<()> {
+    sink.write_u32::<BigEndian>(x)?;
+
+    Ok(())
+}
+
+fn uint_writer(sink: &mut Vec<u8>, width: usize, x: impl Into<u64>) -> eyre::Result<()> {
+    sink.write_uint::<BigEndian>(x.into(), width)?;
+
+    Ok(())
+}
+
+fn uint_type(width: usize) -> SectionType {
+    match width {
+        1 => SectionType::U8,
+        2 => SectionType::U16,
+        3 => SectionType::U24,
+        4 => SectionType::U32,
+        _ => unreachable!("no section type for width {}", width),
+    }
+}
+
+fn sparse_type(width: usize) -> SectionType {
+    match width {
+        1 => SectionType::SparseU8,
+        2 => SectionType::SparseU16,
+        3 => SectionType::SparseU24,
+        4 => SectionType::SparseU32,
+        _ => unreachable!("no sparse section type for width {}", width),
+    }
+}
+
+// Writes a multi-stage trie (see trie.rs) of the values for each point,
+// in whichever layout is smallest for this field, with values that are
+// width bytes wide.
+fn write_sparse<T, U: Copy + Eq + Hash + Debug + Into<u64>, G: FnMut(&T) -> Option<U>>(
+    out: &mut Output,
+    source: &[T],
+    path: &str,
+    width: usize,
+    default: U,
+    mut getter: G,
+) -> eyre::Result<()> {
+    let values = source
+        .iter()
+        .map(|x| {
+            let value = getter(x);
+            assert_ne!(value, Some(default));
+            value
+        })
+        .collect::<Vec<_>>();
+
+    let Some(pages) = out.chunk_pages else {
+        return write_trie(out, path, &values, width, default);
+    };
+    // chunks where no point has a value are left out, like empty blocks
+    for (i, values) in values.chunks(pages * 256).enumerate() {
+        if values.iter().all(|x| x.is_none()) {
+            continue;
+        }
+        let chunk_path = format!(
+            "{}.{:04X}.bin",
+            path.strip_suffix(".bin").unwrap(),
+            i * pages * 256
+        );
+        write_trie(out, &chunk_path, values, width, default)?;
+        out.types.remove(&chunk_path);
+        out.chunks[i].insert(path.to_owned(), chunk_path);
+    }
+    out.types.insert(path.to_owned(), sparse_type(width));
+
+    Ok(())
+}
+
+fn write_trie<U: Copy + Eq + Hash + Into<u64>>(
+    out: &mut Output,
+    path: &str,
+    values: &[Option<U>],
+    width: usize,
+    default: U,
+) -> eyre::Result<()> {
+    let trie = Trie::smallest(values, default, width);
+
+    write(out, path, sparse_type(width), |sink| {
+        sink.write_all(&trie.header(width))?;
+        for &number in trie.first.iter().chain(&trie.index) {
+            u16_writer(sink, number)?;
+        }
+        for &value in &trie.data {
+            uint_writer(sink, width, value)?;
+        }
+
+        Ok(())
+    })?;
+
+    let pages = values
+        .chunks(256)
+        .filter(|x| x.iter().any(|x| x.is_some()))
+        .count();
+    out.sparse_sizes.push(SparseSize {
+        path: path.to_owned(),
+        layout: trie.layout,
+        paged: (values.len() >> 8) * 2 + pages * 256 * width,
+        trie: trie.len(width),
+        blocks: trie.blocks,
+        stored_blocks: trie.data.len() >> trie.layout.data_bits,
+    });
+
+    Ok(())
+}
+
+fn write_pool_indices<T, G: FnMut(&T) -> Option<Rc<str>>>(
+    out: &mut Output,
+    source: &[T],
+    pool: &Pool,
+    path: &str,
+    mut getter: G,
+) -> eyre::Result<()> {
+    let width = index_width(pool.len());
+    write_sparse(out, source, path, width, no_index(width), |x| {
+        getter(x).map(|x| pool.r#use(&x).try_into().unwrap())
+    })
+}
+
+fn write_name_files(out: &mut Output, source: &[Option<String>]) -> eyre::Result<()> {
+    let names = source.iter().flatten().map(|x| &**x).collect::<Vec<_>>();
+    let distinct = names.iter().collect::<BTreeSet<_>>();
+    let mut text = vec![];
+    write_strings(&mut text, &distinct.into_iter().collect::<Vec<_>>())?;
+    let (names, offsets) = Names::encode(&names);
+    let mut tokens = vec![];
+    write_strings(&mut tokens, &names.tokens)?;
+
+    write(out, "data.nametok.bin", SectionType::Strings, |sink| {
+        sink.write_all(&tokens)?;
+
+        Ok(())
+    })?;
+
+    // see names.rs
+    write(out, "data.nameseq.bin", SectionType::Records, |sink| {
+        sink.write_all(&names.data)?;
+
+        Ok(())
+    })?;
+
+    // for each point: offset of its name in data.nameseq.bin
+    let mut offsets = offsets.into_iter();
+    let width = index_width(names.data.len());
+    write_sparse(out, source, "data.name.bin", width, no_index(width), |x| {
+        x.as_ref()
+            .map(|_| offsets.next().unwrap().try_into().unwrap())
+    })?;
+
+    println!(
+        "Names: {} bytes as text → {} bytes ({:.1}%), {} tokens in {} bytes and {} bytes of token sequences",
+        text.len(),
+        tokens.len() + names.data.len(),
+        percent_saved(text.len(), tokens.len() + names.data.len()),
+        names.tokens.len(),
+        tokens.len(),
+        names.data.len()
+    );
+
+    Ok(())
+}
+
+fn write_alias_files(
+    out: &mut Output,
+    source: &[&[Alias]],
+    pool: &Pool,
+    prefix: &str,
+) -> eyre::Result<()> {
+    write_string_list_files(out, source, pool, &format!("{}alias", prefix), |x| {
+        (&x.inner, x.r#type as u8)
+    })
+}
+
+fn write_variant_files(out: &mut Output, source: &[Details]) -> eyre::Result<()> {
+    let variant = source.iter().map(|x| &*x.variant).collect::<Vec<_>>();
+    write_point_list_files(out, &variant, "data.var", |x| x.point)?;
+
+    write(out, "data.vart.bin", SectionType::U8, |sink| {
+        for variant in variant.iter().copied().flatten() {
+            u8_writer(sink, variant.r#type as u8)?;
+        }
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+fn write_names_list_files(
+    out: &mut Output,
+    source: &[Details],
+    names_list: NamesListState,
+) -> eyre::Result<()> {
+    let NamesListState {
+        popularity,
+        subheaders,
+        ..
+    } = names_list;
+    let report = popularity.report();
+
+    write(out, "data.nl.string.bin", SectionType::Strings, |sink| {
+        write_strings(sink, &report)
+    })?;
+
+    let pool = Pool::from(&report);
+    let note = source.iter().map(|x| &*x.nlnote).collect::<Vec<_>>();
+    write_string_list_files(out, &note, &pool, "data.nl.note", |x| {
+        (&x.inner, x.r#type as u8)
+    })?;
+    let xref = source.iter().map(|x| &*x.nlxref).collect::<Vec<_>>();
+    write_point_list_files(out, &xref, "data.nl.xref", |&x| x)?;
+
+    // for each subheader: first, last (u32), name (string index)
+    let width = out.index_width("data.nl.subheader.bin", pool.len());
+    write(out, "data.nl.subheader.bin", SectionType::Records, |sink| {
+        for (first, last, name) in subheaders {
+            u32_writer(sink, first.try_into().unwrap())?;
+            u32_writer(sink, last.try_into().unwrap())?;
+            uint_writer(sink, width, pool.r#use(&name) as u64)?;
+        }
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+// Counts and base indices for a list of items at each point, which
+// clients use to find the items for a point in the other files.
+fn write_list_files<T>(out: &mut Output, source: &[&[T]], stem: &str) -> eyre::Result<()> {
+    let mut counts = Vec::default();
+    let mut indices = Vec::default();
+    let mut index = 0usize;
+
+    for &list in source {
+        let count: Option<u8> = match list.len() {
+            0 => None,
+            x => Some(x.try_into().expect("list count overflow")),
+        };
+        counts.push(count);
+        indices.push(count.map(|_| index));
+        index += list.len();
+    }
+
+    write_sparse(out, &counts, &format!("{}c.bin", stem), 1, 0, |&x| x)?;
+
+    let width = index_width(index);
+    write_sparse(
+        out,
+        &indices,
+        &format!("{}i.bin", stem),
+        width,
+        no_index(width),
+        |&x| x.map(|x| x.try_into().unwrap()),
+    )?;
+
+    Ok(())
+}
+
+fn write_string_list_files<T, G: FnMut(&T) -> (&Rc<str>, u8)>(
+    out: &mut Output,
+    source: &[&[T]],
+    pool: &Pool,
+    stem: &str,
+    mut getter: G,
+) -> eyre::Result<()> {
+    write_list_files(out, source, stem)?;
+
+    let path = format!("{}s.bin", stem);
+    let width = out.index_width(&path, pool.len());
+    write(out, &path, uint_type(width), |sink| {
+        for item in source.iter().copied().flatten() {
+            let (string, _) = getter(item);
+            uint_writer(sink, width, pool.r#use(string) as u64)?;
+        }
+
+        Ok(())
+    })?;
+
+    write(out, &format!("{}t.bin", stem), SectionType::U8, |sink| {
+        for item in source.iter().copied().flatten() {
+            let (_, r#type) = getter(item);
+            u8_writer(sink, r#type)?;
+        }
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+fn write_point_list_files<T, G: FnMut(&T) -> usize>(
+    out: &mut Output,
+    source: &[&[T]],
+    stem: &str,
+    mut getter: G,
+) -> eyre::Result<()> {
+    write_list_files(out, source, stem)?;
+
+    write(out, &format!("{}p.bin", stem), SectionType::U32, |sink| {
+        for item in source.iter().copied().flatten() {
+            u32_writer(sink, getter(item).try_into().unwrap())?;
+        }
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+// Maps each toneless or tone-numbered kMandarin key to the points with
+// that reading.
+fn pinyin_index(source: &[Details]) -> BTreeMap<String, BTreeSet<usize>> {
+    let mut result = BTreeMap::<_, BTreeSet<_>>::default();
+
+    for (i, details) in source.iter().enumerate() {
+        if let Some(readings) = details.unihan.get("kMandarin") {
+            for key in pinyin_keys(readings) {
+                result.entry(key).or_default().insert(i);
+            }
+        }
+    }
+
+    result
+}
+
+fn write_pinyin_files(out: &mut Output, source: &[Details]) -> eyre::Result<()> {
+    let index = pinyin_index(source);
+
+    // sorted keys, for binary search in the client
+    write(out, "data.pyk.bin", SectionType::Json, |sink| {
+        let keys = index.keys().collect::<Vec<_>>();
+        write!(sink, "{}", serde_json::to_string(&keys)?)?;
+
+        Ok(())
+    })?;
+
+    // for each key: start, len (u32)
+    write(out, "data.pyi.bin", SectionType::U32, |sink| {
+        let mut start = 0u32;
+
+        for points in index.values() {
+            let len = points.len().try_into().unwrap();
+            u32_writer(sink, start)?;
+            u32_writer(sink, len)?;
+            start += len;
+        }
+
+        Ok(())
+    })?;
+
+    write(out, "data.pyp.bin", SectionType::U32, |sink| {
+        for &point in index.values().flatten() {
+            u32_writer(sink, point.try_into().unwrap())?;
+        }
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+// Groups ideographs by radical, then simplification, then residual
+// strokes, in the order of a radical-stroke dictionary index.
+fn radical_stroke_groups(source: &[Details]) -> BTreeMap<(usize, usize, isize), Vec<usize>> {
+    let mut result = BTreeMap::<_, Vec<_>>::default();
+
+    for (i, details) in source.iter().enumerate() {
+        if let Some(rs) = details.uhrs {
+            result.entry(rs).or_default().push(i);
+        }
+    }
+
+    result
+}
+
+fn write_radical_stroke_files(
+    out: &mut Output,
+    source: &[Details],
+    radicals: &[Radical],
+) -> eyre::Result<()> {
+    write_sparse(out, source, "data.uhrs.bin", 2, 0, |x| {
+        x.uhrs.map(|(radical, simplified, residual)| {
+            let residual = (residual + RESIDUAL_OFFSET) as usize;
+            ((radical << 8) | (simplified << 6) | residual) as u16
+        })
+    })?;
+    write_sparse(out, source, "data.uhts.bin", 1, 0u8, |x| {
+        x.uhts
+            .map(|x| x.try_into().expect("total strokes overflow"))
+    })?;
+
+    // for each group: radical, simplified, residual + RESIDUAL_OFFSET (u8),
+    // start, len (u32), then the points in each group (u32)
+    write(out, "data.rsindex.bin", SectionType::Records, |sink| {
+        let groups = radical_stroke_groups(source);
+        let mut start = 0u32;
+
+        for (&(radical, simplified, residual), points) in &groups {
+            let len = points.len().try_into().unwrap();
+            u8_writer(sink, radical.try_into().unwrap())?;
+            u8_writer(sink, simplified.try_into().unwrap())?;
+            u8_writer(sink, (residual + RESIDUAL_OFFSET).try_into().unwrap())?;
+            u32_writer(sink, start)?;
+            u32_writer(sink, len)?;
+            start += len;
+        }
+
+        for &point in groups.values().flatten() {
+            u32_writer(sink, point.try_into().unwrap())?;
+        }
+
+        Ok(())
+    })?;
+
+    // for each radical: radical, simplified (u8), CJK radical point or 0,
+    // CJK unified ideograph point (u32)
+    write(out, "data.radical.bin", SectionType::Records, |sink| {
+        for &(radical, simplified, character, ideograph) in radicals {
+            u8_writer(sink, radical.try_into().unwrap())?;
+            u8_writer(sink, simplified.try_into().unwrap())?;
+            u32_writer(sink, character.unwrap_or(0).try_into().unwrap())?;
+            u32_writer(sink, ideograph.try_into().unwrap())?;
+        }
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+fn write_locale_files(
+    out: &mut Output,
+    code: &str,
+    locale: Locale,
+    sequences: &Sequences,
+) -> eyre::Result<()> {
+    let prefix = format!("data.{}.", code);
+    let Locale {
+        popularity,
+        points,
+        sequences: locale_sequences,
+    } = locale;
+    let report = popularity.report();
+
+    write(
+        out,
+        &format!("{}string.bin", prefix),
+        SectionType::Strings,
+        |sink| write_strings(sink, &report),
+    )?;
+
+    let pool = Pool::from(&report);
+
+    write_pool_indices(out, &points, &pool, &format!("{}name.bin", prefix), |x| {
+        x.name.map_clone()
+    })?;
+    let alias = points.iter().map(|x| &*x.alias).collect::<Vec<_>>();
+    write_alias_files(out, &alias, &pool, &prefix)?;
+    write_sequence_aliases(out, sequences, &pool, &format!("{}seqa.bin", prefix), |x| {
+        locale_sequences
+            .get(&x.points)
+            .map(|x| x.alias.clone())
+            .unwrap_or_default()
+    })?;
+    write_sequence_names(out, sequences, &pool, &format!("{}seqn.bin", prefix), |x| {
+        locale_sequences
+            .get(&x.points)
+            .and_then(|x| x.name.clone())
+            .map(|x| (x, false))
+            .into_iter()
+            .collect()
+    })?;
+
+    Ok(())
+}
+
+fn write_emoji_test_files(
+    out: &mut Output,
+    source: &[Details],
+    sequences: &Sequences,
+    pool: &Pool,
+) -> eyre::Result<()> {
+    let indices = sequences.indices();
+    let sequence_width = index_width(indices.len());
+    // the fully-qualified form of each emoji, as a sequence index
+    let qualified = |x: &EmojiTest| -> eyre::Result<Option<usize>> {
+        x.qualified
+            .as_ref()
+            .map(|x| {
+                indices
+                    .get(&**x)
+                    .copied()
+                    .ok_or_eyre("fully-qualified form of emoji is not a sequence")
+            })
+            .transpose()
+    };
+    let equal = source
+        .iter()
+        .map(|x| Ok(x.etest.as_ref().map(qualified).transpose()?.flatten()))
+        .collect::<eyre::Result<Vec<_>>>()?;
+
+    write_pool_indices(out, source, pool, "data.egroup.bin", |x| {
+        x.etest.as_ref().map(|x| x.group.clone())
+    })?;
+    write_pool_indices(out, source, pool, "data.esubgroup.bin", |x| {
+        x.etest.as_ref().map(|x| x.subgroup.clone())
+    })?;
+    write_pool_indices(out, source, pool, "data.eversion.bin", |x| {
+        x.etest.as_ref().map(|x| x.version.clone())
+    })?;
+    write_sparse(out, source, "data.estatus.bin", 1, 0, |x| {
+        x.etest.as_ref().map(|x| x.status as u8)
+    })?;
+    write_sparse(out, source, "data.eorder.bin", 2, 0xFFFFu16, |x| {
+        x.etest
+            .as_ref()
+            .map(|x| x.order.try_into().expect("emoji order overflow"))
+    })?;
+    write_sparse(
+        out,
+        &equal,
+        "data.equal.bin",
+        sequence_width,
+        no_index(sequence_width),
+        |&x| x.map(|x| x.try_into().unwrap()),
+    )?;
+
+    // for each sequence: order (u16), group, subgroup, version (string
+    // indices), qualified (sequence index), then status (u8), or all ones
+    // and 0 if not in emoji-test.txt; both kinds of index share a width
+    let width = out.index_width("data.seqe.bin", pool.len().max(indices.len()));
+    let qualified = sequences
+        .buckets
+        .values()
+        .flatten()
+        .map(|x| x.etest.as_ref().map(qualified).transpose())
+        .collect::<eyre::Result<Vec<_>>>()?;
+    write(out, "data.seqe.bin", SectionType::Records, |sink| {
+        for (sequence, qualified) in sequences.buckets.values().flatten().zip(&qualified) {
+            if let Some(etest) = &sequence.etest {
+                let order = etest.order.try_into().expect("emoji order overflow");
+                u16_writer(sink, order)?;
+                for string in [&etest.group, &etest.subgroup, &etest.version] {
+                    uint_writer(sink, width, pool.r#use(string) as u64)?;
+                }
+                let qualified = qualified.flatten().map(|x| x as u32);
+                uint_writer(sink, width, qualified.unwrap_or(no_index(width)))?;
+                u8_writer(sink, etest.status as u8)?;
+            } else {
+                u16_writer(sink, 0xFFFF)?;
+                for _ in 0..4 {
+                    uint_writer(sink, width, no_index(width))?;
+                }
+                u8_writer(sink, 0)?;
+            }
+        }
+
+        Ok(())
+    })?;
+
+    Ok(())
+}
+
+fn write_sequence_files(out: &mut Output, sequences: &Sequences, pool: &Pool) -> eyre::Result<()> {
+    write(out, "data.seqk.bin", SectionType::U8, |sink| {
+        for sequence in sequences.buckets.values().flatten() {
+            u8_writer(sink, sequence.bits.bits())?;
+        }
+
+        Ok(())
+    })?;
+
+    // for each bucket: first and second point (u32), start (sequence
+    // index), len (u8)
+    let count = sequences.buckets.values().map(|x| x.len()).sum();
+    let width = out.index_width("data.seqb.bin", count);
+    write(out, "data.seqb.bin", SectionType::Records, |sink| {
+        let mut start = 0usize;
+
+        for (key, bucket) in sequences.buckets.iter() {
+            let len = bucket
+                .len()
+                .try_into()
+                .expect("sequence bucket len overflow");
+            // eprintln!("{:04X}\t{:04X}\t{}\t{}", key.0, key.1, start, len);
+            u32_writer(sink, key.0.try_into().unwrap())?;
+            u32_writer(sink, key.1.try_into().unwrap())?;
+            uint_writer(sink, width, start as u64)?;
+            u8_writer(sink, len)?;
+            start += usize::from(len);
+        }
+
+        Ok(())
+    })?;
+
+    write(out, "data.seqp.bin", SectionType::Records, |sink| {
+        let mut start = 0;
+        for bucket in sequences.buckets.values() {
+            for sequence in bucket {
+                let len = sequence
+                    .points
+                    .len()
+                    .try_into()
+                    .expect("sequence points len overflow");
+                // let debug = sequence.points.iter().map(|x| format!("{:04X}", x)).reduce(|a, x| format!("{} {}", a, x)).unwrap();
+                // eprintln!("{}\t{}\t{}", start, len, debug);
+                u32_writer(sink, start)?;
+                u8_writer(sink, len)?;
+                start = start
+                    .checked_add(len.into())
+                    .expect("sequence points start overflow");
+            }
+        }
+
+        for bucket in sequences.buckets.values() {
+            for sequence in bucket {
+                for &point in sequence.points.iter() {
+                    u32_writer(sink, point.try_into().unwrap())?;
+                }
+            }
+        }
+
+        Ok(())
+    })?;
+
+    write_sequence_aliases(out, sequences, pool, "data.seqa.bin", |x| x.alias.clone())?;
+    write_sequence_names(out, sequences, pool, "data.seqn.bin", |x| {
+        x.names
+            .iter()
+            .cloned()
+            .zip(x.provisional.iter().copied())
+            .collect()
+    })?;
+
+    Ok(())
+}
+
+fn write_sequence_aliases<G: FnMut(&Sequence) -> Vec<Alias>>(
+    out: &mut Output,
+    sequences: &Sequences,
+    pool: &Pool,
+    path: &str,
+    getter: G,
+) -> eyre::Result<()> {
+    let aliases = sequences
+        .buckets
+        .values()
+        .flatten()
+        .map(getter)
+        .collect::<Vec<_>>();
+
+    let width = out.index_width(path, pool.len());
+    write(out, path, SectionType::Records, |sink| {
+        let mut start = 0;
+        for alias in &aliases {
+            let len = alias.len().try_into().expect("sequence alias len overflow");
+            u32_writer(sink, start)?;
+            u8_writer(sink, len)?;
+            start = start
+                .checked_add(len.into())
+                .expect("sequence alias start overflow");
+        }
+
+        for alias in aliases.iter().flatten() {
+            uint_writer(sink, width, pool.r#use(&alias.inner) as u64)?;
+        }
+
+        for alias in aliases.iter().flatten() {
+            u8_writer(sink, alias.r#type as u8)?;
+        }
+
+        Ok(())
+    })
+}
+
+// for each sequence: start (u32), len (u8), then the names (string
+// indices), then whether each name is provisional (u8)
+fn write_sequence_names<G: FnMut(&Sequence) -> Vec<(Rc<str>, bool)>>(
+    out: &mut Output,
+    sequences: &Sequences,
+    pool: &Pool,
+    path: &str,
+    getter: G,
+) -> eyre::Result<()> {
+    let names = sequences
+        .buckets
+        .values()
+        .flatten()
+        .map(getter)
+        .collect::<Vec<_>>();
+
+    let width = out.index_width(path, pool.len());
+    write(out, path, SectionType::Records, |sink| {
+        let mut start = 0;
+        for names in &names {
+            let len = names.len().try_into().expect("sequence names len overflow");
+            u32_writer(sink, start)?;
+            u8_writer(sink, len)?;
+            start = start
+                .checked_add(len.into())
+                .expect("sequence names start overflow");
+        }
+
+        for (name, _) in names.iter().flatten() {
+            uint_writer(sink, width, pool.r#use(name) as u64)?;
+        }
+
+        for &(_, provisional) in names.iter().flatten() {
+            u8_writer(sink, provisional.into())?;
+        }
+
+        Ok(())
+    })
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use data::container::crc32;
+use serde::Deserialize;
+
+use crate::common::mini;
+
+// Points without a value read as 0 in these files, and as all ones in
+// the other sparse files.
+const ZERO_DEFAULT: [&str; 11] = [
+    "data.bits.bin",
+    "data.ebits.bin",
+    "data.gb.bin",
+    "data.estatus.bin",
+    "data.uhts.bin",
+    "data.hlvt.bin",
+    "data.uhrs.bin",
+    "data.aliasc.bin",
+    "data.varc.bin",
+    "data.nl.notec.bin",
+    "data.nl.xrefc.bin",
+];
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    chunk_pages: usize,
+    files: BTreeMap<String, String>,
+    chunks: Vec<Chunk>,
+}
+
+#[derive(Deserialize)]
+struct Chunk {
+    first: usize,
+    last: usize,
+    files: BTreeMap<String, ChunkFile>,
+}
+
+#[derive(Deserialize)]
+struct ChunkFile {
+    path: String,
+    hash: String,
+}
+
+// Writes the mini fixtures both whole and in 16-page chunks, then
+// checks that the chunks hold the same values as the whole files.
+#[test]
+fn mini_chunks() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (whole, chunked) = (dir.join("chunks-whole"), dir.join("chunks"));
+    for dir in [&whole, &chunked] {
+        let _ = fs::remove_dir_all(dir);
+        fs::create_dir_all(dir).unwrap();
+    }
+    data::write_all(mini(), &whole).unwrap();
+    data::write_chunked(mini(), &chunked, 16).unwrap();
+
+    let read = |dir: &Path, path: &str| fs::read(dir.join(path)).unwrap();
+    let manifest = read(&chunked, "data.manifest.json");
+    let manifest: Manifest = serde_json::from_slice(&manifest).unwrap();
+    assert_eq!(manifest.chunk_pages, 16);
+    assert_eq!(manifest.chunks.len(), 0x110);
+
+    // every file is listed once, with its hash
+    let mut listed = manifest.files.clone();
+    for chunk in &manifest.chunks {
+        for file in chunk.files.values() {
+            assert!(listed
+                .insert(file.path.clone(), file.hash.clone())
+                .is_none());
+        }
+    }
+    let mut written = fs::read_dir(&chunked)
+        .unwrap()
+        .map(|x| x.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    written.retain(|x| x != "data.manifest.json");
+    written.sort();
+    assert_eq!(listed.keys().cloned().collect::<Vec<_>>(), written);
+    for (path, hash) in &listed {
+        assert_eq!(*hash, format!("{:08x}", crc32(&read(&chunked, path))));
+    }
+
+    // files that aren’t split are the same as when written whole
+    for path in manifest.files.keys() {
+        assert_eq!(read(&chunked, path), read(&whole, path), "{}", path);
+    }
+
+    let sparse = fs::read_dir(&whole)
+        .unwrap()
+        .map(|x| x.unwrap().file_name().into_string().unwrap())
+        .filter(|x| !manifest.files.contains_key(x))
+        .collect::<Vec<_>>();
+    assert!(sparse.contains(&"data.name.bin".to_owned()));
+    for name in sparse {
+        let bytes = read(&whole, &name);
+        let width = usize::from(bytes[0]);
+        let default = if ZERO_DEFAULT.contains(&&*name) {
+            0
+        } else {
+            ((1u64 << (8 * width)) - 1) as u32
+        };
+        for chunk in &manifest.chunks {
+            let len = chunk.last + 1 - chunk.first;
+            let part = chunk.files.get(&name).map(|x| read(&chunked, &x.path));
+            for point in 0..len {
+                let expected = get(&bytes, 0x110000, chunk.first + point);
+                let actual = part.as_ref().and_then(|x| get(x, len, point));
+                assert_eq!(
+                    actual.unwrap_or(default),
+                    expected.unwrap_or(default),
+                    "{} U+{:04X}",
+                    name,
+                    chunk.first + point
+                );
+            }
+        }
+    }
+}
+
+// Writing whole files over chunked ones removes the manifest, so that
+// the client doesn't load the old chunks.
+#[test]
+fn write_all_removes_manifest() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("chunks-rewritten");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    data::write_chunked(mini(), &dir, 256).unwrap();
+    assert!(dir.join("data.manifest.json").exists());
+
+    data::write_all(mini(), &dir).unwrap();
+    assert!(!dir.join("data.manifest.json").exists());
+}
+
+#[test]
+fn chunk_size_is_checked() {
+    let error = data::write_chunked(mini(), Path::new(env!("CARGO_TARGET_TMPDIR")), 100);
+
+    assert_eq!(
+        error.unwrap_err().to_string(),
+        "chunk size must be a positive multiple of 16 pages"
+    );
+}
+
+// Looks up a point in a trie (see trie.rs) that covers len points, or
+// returns None if the point is in a block that isn’t stored.
+fn get(bytes: &[u8], len: usize, point: usize) -> Option<u32> {
+    let uint = |offset: usize, width: usize| {
+        bytes[offset..][..width]
+            .iter()
+            .fold(0, |a, &x| (a << 8) | u32::from(x)) as usize
+    };
+    let width = usize::from(bytes[0]);
+    let (index_bits, data_bits) = (bytes[2], bytes[3]);
+    let shift = index_bits + data_bits;
+
+    let mut offset = 8;
+    let mut block = uint(offset + (point >> shift) * 2, 2);
+    offset += (len >> shift) * 2;
+    if index_bits > 0 {
+        if block != 0xFFFF {
+            let i = (point >> data_bits) & ((1 << index_bits) - 1);
+            block = uint(offset + ((block << index_bits) + i) * 2, 2);
+        }
+        offset += (uint(4, 2) << index_bits) * 2;
+    }
+
+    let i = (block << data_bits) + (point & ((1 << data_bits) - 1));
+    (block != 0xFFFF).then(|| uint(offset + i * width, width) as u32)
+}
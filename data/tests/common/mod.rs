@@ -0,0 +1,78 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre;
+use data::{Ucd, DEFAULT_UNIHAN_FIELDS};
+
+// A small, hand-checked subset of the UCD (see tests/fixtures/mini).
+#[allow(dead_code)]
+pub fn mini() -> Ucd {
+    load_mini(&[], &DEFAULT_UNIHAN_FIELDS)
+}
+
+// The mini fixtures, plus names and keywords for the given locales,
+// keeping the given Unihan fields.
+#[allow(dead_code)]
+pub fn load_mini(locales: &[&str], unihan_fields: &[&str]) -> Ucd {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mini");
+    data::load(&dir, locales, unihan_fields).expect("failed to load mini fixtures")
+}
+
+// A copy of the mini fixtures in a scratch directory, changed by edit,
+// for tests of input that the fixtures should not have.
+#[allow(dead_code)]
+pub fn load_mini_edited(name: &str, edit: impl FnOnce(&Path)) -> eyre::Result<Ucd> {
+    let mini = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mini");
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = fs::remove_dir_all(&dir);
+    copy_dir(&mini, &dir);
+    edit(&dir);
+    data::load(&dir, &[], &DEFAULT_UNIHAN_FIELDS)
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir(&path, &to.join(entry.file_name()));
+        } else {
+            fs::copy(&path, to.join(entry.file_name())).unwrap();
+        }
+    }
+}
+
+// Inputs that ./update.sh fetches but that aren’t checked in.
+#[allow(dead_code)]
+const NOT_CHECKED_IN: [&str; 9] = [
+    "CJKRadicals.txt",
+    "DerivedName.txt",
+    "NamedSequencesProv.txt",
+    "NamesList.txt",
+    "StandardizedVariants.txt",
+    "emoji-variation-sequences.txt",
+    "Unihan_IRGSources.txt",
+    "Unihan_Readings.txt",
+    "Unihan_Variants.txt",
+];
+
+// The real UCD in $UCD_DIR (as in main.rs), which must then be complete,
+// or in the data directory if ./update.sh has fetched the rest of it.
+#[allow(dead_code)]
+pub fn real() -> Option<Ucd> {
+    let dir = match env::var_os("UCD_DIR") {
+        Some(x) => PathBuf::from(x),
+        None => {
+            let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+            if let Some(path) = NOT_CHECKED_IN.iter().find(|&x| !dir.join(x).exists()) {
+                eprintln!("skipping: {} not found, run ./update.sh first", path);
+                return None;
+            }
+            dir
+        }
+    };
+
+    Some(data::load(&dir, &[], &DEFAULT_UNIHAN_FIELDS).expect("failed to load real UCD"))
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use data::container::{crc32, Container, Section, SectionType, FORMAT_VERSION, MAGIC};
+
+use crate::common::mini;
+
+fn example() -> Container {
+    Container {
+        unicode_version: "16.0.0".to_owned(),
+        emoji_version: "16.0".to_owned(),
+        sections: vec![
+            Section {
+                name: "data.string.bin".to_owned(),
+                r#type: SectionType::Json,
+                data: br#"["a","bc"]"#.to_vec(),
+            },
+            Section {
+                name: "data.pyp.bin".to_owned(),
+                r#type: SectionType::U32,
+                data: vec![0, 0, 0x51, 0x70],
+            },
+        ],
+    }
+}
+
+fn bytes(container: &Container) -> Vec<u8> {
+    let mut result = Vec::default();
+    container.write(&mut result).unwrap();
+
+    result
+}
+
+#[test]
+fn crc32_check_value() {
+    assert_eq!(crc32(b"123456789"), 0xCBF43926);
+}
+
+#[test]
+fn round_trip() {
+    let container = example();
+    let bytes = bytes(&container);
+
+    assert_eq!(bytes[..8], MAGIC);
+    assert_eq!(bytes[8..10], FORMAT_VERSION.to_be_bytes());
+    assert_eq!(Container::read(&bytes).unwrap(), container);
+}
+
+#[test]
+fn sections_are_aligned() {
+    let bytes = bytes(&example());
+    let container = Container::read(&bytes).unwrap();
+
+    for section in &container.sections {
+        let offset = bytes
+            .windows(section.data.len())
+            .position(|x| x == section.data)
+            .unwrap();
+        assert_eq!(offset % 4, 0, "{}", section.name);
+    }
+}
+
+#[test]
+fn corruption_is_detected() {
+    let bytes = bytes(&example());
+
+    let mut corrupt = bytes.clone();
+    *corrupt.last_mut().unwrap() ^= 1;
+    let error = Container::read(&corrupt).unwrap_err().to_string();
+    assert_eq!(error, "section data.pyp.bin checksum mismatch");
+
+    // "16.0.0" → "16.1.0"
+    let mut corrupt = bytes.clone();
+    corrupt[14] = b'1';
+    let error = Container::read(&corrupt).unwrap_err().to_string();
+    assert_eq!(error, "container header checksum mismatch");
+
+    let error = Container::read(&bytes[..bytes.len() - 1]).unwrap_err();
+    assert_eq!(error.to_string(), "section data.pyp.bin out of bounds");
+
+    let mut corrupt = bytes.clone();
+    corrupt[..8].copy_from_slice(b"notmagic");
+    let error = Container::read(&corrupt).unwrap_err().to_string();
+    assert_eq!(error, "not a container (bad magic)");
+
+    let mut corrupt = bytes;
+    corrupt[9] += 1;
+    let error = Container::read(&corrupt).unwrap_err().to_string();
+    assert!(error.starts_with("unsupported container format version"));
+}
+
+// Each section of the container matches the loose file of the same name.
+#[test]
+fn mini_container() {
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("mini-container");
+    let _ = fs::remove_dir_all(&out);
+    fs::create_dir_all(&out).unwrap();
+    data::write_container(mini(), &out.join("data.bin")).unwrap();
+    data::write_all(mini(), &out).unwrap();
+
+    let container = Container::read(&fs::read(out.join("data.bin")).unwrap()).unwrap();
+    assert_eq!(container.unicode_version, "16.0.0");
+    assert_eq!(container.emoji_version, "16.0");
+
+    let mut loose = fs::read_dir(&out)
+        .unwrap()
+        .map(|x| x.unwrap().file_name().into_string().unwrap())
+        .filter(|x| x != "data.bin")
+        .collect::<Vec<_>>();
+    let mut names = container
+        .sections
+        .iter()
+        .map(|x| x.name.clone())
+        .collect::<Vec<_>>();
+    loose.sort();
+    names.sort();
+    assert_eq!(names, loose);
+
+    for name in names {
+        let section = container.section(&name).unwrap();
+        assert_eq!(section.data, fs::read(out.join(&name)).unwrap(), "{}", name);
+    }
+    let r#type = |name| container.section(name).unwrap().r#type;
+    assert_eq!(r#type("egcbreak.ts"), SectionType::Text);
+    assert_eq!(r#type("data.string.bin"), SectionType::Strings);
+    assert_eq!(r#type("data.bits.bin"), SectionType::SparseU8);
+    assert_eq!(r#type("data.name.bin"), SectionType::SparseU16);
+    assert_eq!(r#type("data.aliass.bin"), SectionType::U16);
+    assert_eq!(r#type("data.varp.bin"), SectionType::U32);
+    assert_eq!(r#type("data.seqb.bin"), SectionType::Records);
+}
@@ -0,0 +1,571 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use data::derived_name;
+use data::details::AliasType::*;
+use data::details::{
+    Alias, Bits, Details, EmojiBits, EmojiStatus, GraphemeBreak, HangulSyllableType, Note,
+    NoteType, Variant, VariantType,
+};
+use data::sequence::SequenceBits;
+
+use crate::common::{load_mini, load_mini_edited, mini};
+
+#[test]
+fn control_characters() {
+    let ucd = mini();
+
+    assert_eq!(
+        ucd.ud[0x0000],
+        Details::builder()
+            .alias(&[("NULL", Unicode1), ("NULL", Control), ("NUL", Abbreviation)])
+            .gb(GraphemeBreak::Control)
+            .gc("Control (Cc)")
+            .block("Basic Latin")
+            .age("Unicode 1.1")
+            .build(),
+    );
+    assert_eq!(
+        ucd.ud[0x0080],
+        Details::builder()
+            .alias(&[("PADDING CHARACTER", Figment), ("PAD", Abbreviation)])
+            .gb(GraphemeBreak::Control)
+            .gc("Control (Cc)")
+            .block("Latin-1 Supplement")
+            .age("Unicode 1.1")
+            .build()
+    );
+}
+
+#[test]
+fn alias_order() {
+    let ucd = mini();
+
+    // Unicode_1_Name first, then NameAliases.txt in file order, then
+    // NamesList.txt informal aliases not already listed
+    assert_eq!(
+        ucd.ud[0x000A],
+        Details::builder()
+            .alias(&[
+                ("LINE FEED (LF)", Unicode1),
+                ("LINE FEED", Control),
+                ("NEW LINE", Control),
+                ("END OF LINE", Control),
+                ("LF", Abbreviation),
+                ("NL", Abbreviation),
+                ("EOL", Abbreviation),
+                ("new line (NL)", Informal),
+                ("end of line (EOL)", Informal),
+            ])
+            .gb(GraphemeBreak::Lf)
+            .gc("Control (Cc)")
+            .block("Basic Latin")
+            .age("Unicode 1.1")
+            .build()
+    );
+    assert_eq!(
+        ucd.ud[0xFEFF],
+        Details::builder()
+            .name("ZERO WIDTH NO-BREAK SPACE")
+            .alias(&[
+                ("BYTE ORDER MARK", Unicode1),
+                ("BYTE ORDER MARK", Alternate),
+                ("BOM", Abbreviation),
+                ("ZWNBSP", Abbreviation),
+            ])
+            .gb(GraphemeBreak::Control)
+            .gc("Format (Cf)")
+            .block("Arabic Presentation Forms-B")
+            .age("Unicode 1.1")
+            .build()
+    );
+}
+
+#[test]
+fn names_and_corrections() {
+    let ucd = mini();
+
+    assert_eq!(
+        ucd.ud[0x039B],
+        Details::builder()
+            .name("GREEK CAPITAL LETTER LAMDA")
+            .alias(&[("GREEK CAPITAL LETTER LAMBDA", Unicode1)])
+            .gc("Uppercase Letter (Lu)")
+            .block("Greek and Coptic")
+            .age("Unicode 1.1")
+            .build(),
+    );
+    assert_eq!(
+        ucd.ud[0xFE18],
+        Details::builder()
+            .name("PRESENTATION FORM FOR VERTICAL RIGHT WHITE LENTICULAR BRAKCET")
+            .alias(&[(
+                "PRESENTATION FORM FOR VERTICAL RIGHT WHITE LENTICULAR BRACKET",
+                Correction,
+            )])
+            .gc("Close Punctuation (Pe)")
+            .block("Vertical Forms")
+            .age("Unicode 4.1")
+            .nlnote(vec![Note {
+                inner: r#"misspelling of "BRACKET" in character name is a known defect"#.into(),
+                r#type: NoteType::Informative,
+            }])
+            .build()
+    );
+}
+
+#[test]
+fn names_list() {
+    let ucd = mini();
+
+    assert_eq!(
+        ucd.ud[0x0023].alias,
+        Details::builder()
+            .alias(&[("pound sign, hash, crosshatch, octothorpe", Informal)])
+            .build()
+            .alias
+    );
+    // both “x (numero sign - 2116)” and “x 1D7CE” forms
+    assert_eq!(ucd.ud[0x0023].nlxref, [0x2116, 0x266F]);
+    assert_eq!(ucd.ud[0x0030].nlxref, [0x1D7CE]);
+    assert_eq!(
+        ucd.ud[0x0030].nlnote,
+        [Note {
+            inner: "0030 FE00 short diagonal stroke form".into(),
+            r#type: NoteType::Variation,
+        }]
+    );
+}
+
+#[test]
+fn names_list_bad_xref() {
+    let error = load_mini_edited("mini-nl-xref", |dir| {
+        let path = dir.join("NamesList.txt");
+        let mut source = fs::read_to_string(&path).unwrap();
+        source.push_str("\tx (no code point here)\n");
+        fs::write(&path, source).unwrap();
+    })
+    .err()
+    .expect("bad cross reference was accepted");
+    assert_eq!(
+        format!("{:#}", error),
+        "bad cross reference in NamesList.txt: \tx (no code point here)"
+    );
+}
+
+#[test]
+fn unihan() {
+    let ucd = mini();
+
+    assert_eq!(
+        ucd.ud[0x5170],
+        Details::builder()
+            .dnrp("CJK UNIFIED IDEOGRAPH-")
+            .gc("Other Letter (Lo)")
+            .block("CJK Unified Ideographs")
+            .age("Unicode 1.1")
+            .unihan(&[
+                ("kCantonese", "laan4"),
+                ("kDefinition", "orchid; elegant, graceful"),
+                ("kMandarin", "lán"),
+            ])
+            .uhrs((12, 0, 3))
+            .uhts(5)
+            .variant(vec![Variant {
+                point: 0x862D,
+                r#type: VariantType::Traditional,
+            }])
+            .bits(Bits::KdefinitionExists | Bits::DerivedNameNr2)
+            .build()
+    );
+    assert_eq!(
+        ucd.ud[0x9FFF],
+        Details::builder()
+            .dnrp("CJK UNIFIED IDEOGRAPH-")
+            .gc("Other Letter (Lo)")
+            .block("CJK Unified Ideographs")
+            .age("Unicode 14.0")
+            .unihan(&[("kMandarin", "xìng")])
+            // residual stroke counts can be negative
+            .uhrs((120, 0, -1))
+            .bits(Bits::DerivedNameNr2.into())
+            .build()
+    );
+    assert_eq!(
+        ucd.ud[0xF900],
+        Details::builder()
+            .dnrp("CJK COMPATIBILITY IDEOGRAPH-")
+            .gc("Other Letter (Lo)")
+            .block("CJK Compatibility Ideographs")
+            .age("Unicode 1.1")
+            .unihan(&[("kDefinition", "how? what?")])
+            .bits(Bits::DerivedNameNr2 | Bits::KdefinitionExists)
+            .build()
+    );
+}
+
+#[test]
+fn hangul_syllables() {
+    let ucd = mini();
+
+    assert_eq!(
+        ucd.ud[0xD4DB],
+        Details::builder()
+            .dnrp("HANGUL SYLLABLE ")
+            .gb(GraphemeBreak::HangulLVT)
+            .gc("Other Letter (Lo)")
+            .block("Hangul Syllables")
+            .age("Unicode 2.0")
+            .hst(HangulSyllableType::Lvt)
+            .hlvt((17, 16, 15))
+            .bits(Bits::DerivedNameNr1.into())
+            .build()
+    );
+    assert_eq!(
+        ucd.ud[0xD788],
+        Details::builder()
+            .dnrp("HANGUL SYLLABLE ")
+            .gb(GraphemeBreak::HangulLV)
+            .gc("Other Letter (Lo)")
+            .block("Hangul Syllables")
+            .age("Unicode 2.0")
+            .hst(HangulSyllableType::Lv)
+            .hlvt((18, 20, 0))
+            .bits(Bits::DerivedNameNr1.into())
+            .build()
+    );
+    assert_eq!(
+        derived_name(&ucd.ud, &ucd.name_rules, 0xD4DB).as_deref(),
+        Some("HANGUL SYLLABLE PWILH")
+    );
+    assert_eq!(
+        derived_name(&ucd.ud, &ucd.name_rules, 0xD788).as_deref(),
+        Some("HANGUL SYLLABLE HI")
+    );
+}
+
+#[test]
+fn ebits() {
+    let ucd = mini();
+
+    assert_eq!(
+        ucd.ud[0x0023].ebits,
+        EmojiBits::Emoji | EmojiBits::EmojiComponent
+    );
+    assert_eq!(
+        ucd.ud[0x263A].ebits,
+        EmojiBits::Emoji | EmojiBits::ExtendedPictographic
+    );
+    assert_eq!(
+        ucd.ud[0x1F600].ebits,
+        EmojiBits::Emoji | EmojiBits::ExtendedPictographic | EmojiBits::EmojiPresentation
+    );
+    assert_eq!(
+        ucd.ud[0x1F3FB].ebits,
+        EmojiBits::Emoji
+            | EmojiBits::EmojiComponent
+            | EmojiBits::EmojiPresentation
+            | EmojiBits::EmojiModifier
+    );
+    assert_eq!(
+        ucd.ud[0x1F44B].ebits,
+        EmojiBits::Emoji
+            | EmojiBits::ExtendedPictographic
+            | EmojiBits::EmojiPresentation
+            | EmojiBits::EmojiModifierBase
+    );
+    assert!(ucd.ud[0x20E3].ebits == EmojiBits::EmojiComponent);
+    assert!(ucd.ud[0x0041].ebits.is_empty());
+}
+
+#[test]
+fn emoji_test() {
+    let ucd = mini();
+    let etest = |point: usize| ucd.ud[point].etest.clone().expect("missing etest");
+
+    assert_eq!(etest(0x1F600).order, 0);
+    assert_eq!(&*etest(0x1F600).group, "Smileys & Emotion");
+    assert_eq!(&*etest(0x1F600).subgroup, "face-smiling");
+    assert_eq!(&*etest(0x1F600).version, "Emoji 1.0");
+    assert_eq!(etest(0x1F3FB).status, EmojiStatus::Component);
+    assert_eq!(etest(0x263A).status, EmojiStatus::Unqualified);
+    assert_eq!(etest(0x263A).qualified, Some(vec![0x263A, 0xFE0F]));
+
+    // names from emoji-test.txt become CLDR aliases, except for
+    // emoji that are not fully qualified
+    assert_eq!(
+        ucd.ud[0x1F44B].alias,
+        Details::builder()
+            .alias(&[("waving hand", Cldr)])
+            .build()
+            .alias
+    );
+    assert!(ucd.ud[0x263A].alias.is_empty());
+}
+
+#[test]
+fn sequences() {
+    let ucd = mini();
+    let sequence = |points: &[usize]| {
+        ucd.sequences
+            .buckets
+            .values()
+            .flatten()
+            .find(|x| x.points == points)
+            .expect("missing sequence")
+    };
+    let names = |points: &[usize]| {
+        sequence(points)
+            .names
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        sequence(&[0x0023, 0xFE0F, 0x20E3]).bits,
+        SequenceBits::NamedSequence | SequenceBits::EmojiTest
+    );
+    assert_eq!(
+        names(&[0x0023, 0xFE0F, 0x20E3]),
+        ["KEYCAP NUMBER SIGN", "keycap: #"]
+    );
+    assert_eq!(
+        sequence(&[0x263A, 0xFE0F]).bits,
+        SequenceBits::NamedSequenceProvisional
+            | SequenceBits::EmojiTest
+            | SequenceBits::EmojiVariation
+    );
+    assert_eq!(
+        names(&[0x263A, 0xFE0F]),
+        [
+            "WHITE SMILING FACE WITH EMOJI PRESENTATION",
+            "smiling face",
+            "WHITE SMILING FACE (emoji style)"
+        ]
+    );
+    // only the name from NamedSequencesProv.txt is provisional
+    assert_eq!(
+        sequence(&[0x263A, 0xFE0F]).provisional,
+        [true, false, false]
+    );
+    assert_eq!(
+        sequence(&[0x0023, 0xFE0F, 0x20E3]).provisional,
+        [false, false]
+    );
+    assert_eq!(
+        names(&[0x263A, 0xFE0E]),
+        ["WHITE SMILING FACE (text style)"]
+    );
+    assert!(sequence(&[0x0030, 0xFE00]).bits == SequenceBits::StandardizedVariant);
+    assert_eq!(names(&[0x0030, 0xFE00]), ["short diagonal stroke form"]);
+    assert_eq!(names(&[0x1F44B, 0x1F3FB]), ["waving hand: light skin tone"]);
+
+    // unqualified sequences are linked to their fully-qualified form,
+    // and have no names of their own
+    let unqualified = sequence(&[0x0023, 0x20E3]);
+    assert!(unqualified.names.is_empty());
+    assert_eq!(
+        unqualified.etest.as_ref().and_then(|x| x.qualified.clone()),
+        Some(vec![0x0023, 0xFE0F, 0x20E3])
+    );
+
+    // sequences are bucketed by their first two points
+    assert_eq!(
+        ucd.sequences.buckets[&(0x0023, 0xFE0F)]
+            .iter()
+            .map(|x| x.points.clone())
+            .collect::<Vec<_>>(),
+        [vec![0x0023, 0xFE0F, 0x20E3], vec![0x0023, 0xFE0F]]
+    );
+}
+
+#[test]
+fn ivd() {
+    let ucd = mini();
+    let sequence = ucd.sequences.get(&[0x5170, 0xE0100]).unwrap();
+    assert_eq!(sequence.bits, SequenceBits::Ivd);
+    assert_eq!(&*sequence.names, ["Adobe-Japan1 CID+4420".into()]);
+    let sequence = ucd.sequences.get(&[0x5170, 0xE0101]).unwrap();
+    assert_eq!(&*sequence.names, ["Moji_Joho MJ014075".into()]);
+}
+
+// Loads the mini fixtures with another line in IVD_Sequences.txt, and
+// returns the error.
+fn ivd_error(name: &str, line: &str) -> String {
+    let error = load_mini_edited(name, |dir| {
+        let path = dir.join("IVD_Sequences.txt");
+        let mut source = fs::read_to_string(&path).unwrap();
+        source.push_str(line);
+        fs::write(&path, source).unwrap();
+    })
+    .err()
+    .expect("bad IVD sequence was accepted");
+    format!("{:#}", error)
+}
+
+#[test]
+fn ivd_bad_selector() {
+    assert_eq!(
+        ivd_error("mini-ivd-selector", "5170 FE00; Adobe-Japan1; CID+4420\n"),
+        "bad ideographic variation sequence in IVD_Sequences.txt: 5170 FE00"
+    );
+}
+
+#[test]
+fn ivd_unknown_collection() {
+    assert_eq!(
+        ivd_error("mini-ivd-collection", "5170 E0102; Hanyo-Denshi; JA5170\n"),
+        "unknown collection in IVD_Sequences.txt"
+    );
+}
+
+#[test]
+fn ivd_bad_identifier() {
+    assert_eq!(
+        ivd_error("mini-ivd-identifier", "5170 E0102; Moji_Joho; CID+4420\n"),
+        "bad sequence identifier for Moji_Joho: CID+4420"
+    );
+}
+
+#[test]
+fn flags() {
+    let ucd = mini();
+    let names = |points: &[usize]| {
+        ucd.sequences
+            .buckets
+            .values()
+            .flatten()
+            .find(|x| x.points == points)
+            .expect("missing sequence")
+            .names
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+    };
+    let tags = |code: &str| {
+        let tags = code.chars().map(|x| 0xE0000 + x as usize);
+        std::iter::once(0x1F3F4)
+            .chain(tags)
+            .chain([0xE007F])
+            .collect::<Vec<_>>()
+    };
+    let derived = |points: &[usize]| {
+        ucd.sequences
+            .get(points)
+            .is_some_and(|x| x.bits.contains(SequenceBits::DerivedFlag))
+    };
+
+    // flags that emoji-test.txt names are left alone
+    assert_eq!(names(&[0x1F1EC, 0x1F1E7]), ["flag: United Kingdom"]);
+    assert!(!derived(&[0x1F1EC, 0x1F1E7]));
+    assert_eq!(names(&tags("gbeng")), ["flag: England"]);
+    assert!(!derived(&tags("gbeng")));
+
+    // other regions and subdivisions in CLDR are named after it
+    assert_eq!(names(&[0x1F1FD, 0x1F1F0]), ["flag: XK (Kosovo, non-RGI)"]);
+    assert!(derived(&[0x1F1FD, 0x1F1F0]));
+    assert!(derived(&tags("gbsct")));
+
+    // and regions that no input defines are not created
+    assert!(ucd.sequences.get(&[0x1F1F6, 0x1F1F6]).is_none());
+    assert_eq!(
+        ucd.sequences
+            .buckets
+            .values()
+            .flatten()
+            .filter(|x| x.bits.contains(SequenceBits::DerivedFlag))
+            .count(),
+        2
+    );
+    assert_eq!(names(&tags("gbsct")), ["flag: GB-SCT (Scotland, non-RGI)"]);
+}
+
+#[test]
+fn flags_without_cldr() {
+    let ucd = load_mini_edited("mini-no-cldr", |dir| {
+        fs::remove_dir_all(dir.join("cldr")).unwrap();
+    })
+    .unwrap();
+
+    assert_eq!(
+        ucd.sequences
+            .buckets
+            .values()
+            .flatten()
+            .filter(|x| x.bits.contains(SequenceBits::DerivedFlag))
+            .count(),
+        0
+    );
+}
+
+#[test]
+fn annotations() {
+    let ucd = mini();
+    let keywords = |alias: &[Alias]| {
+        alias
+            .iter()
+            .filter(|x| x.r#type == CldrKeyword)
+            .map(|x| x.inner.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    // keywords that repeat a name or alias are left out
+    assert_eq!(keywords(&ucd.ud[0x1F600].alias), ["face", "grin"]);
+
+    // annotations are keyed without emoji presentation selectors, so
+    // they go to the fully-qualified sequence
+    let smiling = ucd.sequences.get(&[0x263A, 0xFE0F]).unwrap();
+    assert_eq!(
+        keywords(&smiling.alias),
+        ["face", "outlined", "relaxed", "smile"]
+    );
+    assert!(keywords(&ucd.ud[0x263A].alias).is_empty());
+
+    // sequences that no other input defines are not created
+    assert!(ucd.sequences.get(&[0x1F44B, 0x1F3FD]).is_none());
+}
+
+#[test]
+fn unihan_fields() {
+    // kHanyuPinyin is not kept by default
+    assert!(!mini().ud[0x5170].unihan.contains_key("kHanyuPinyin"));
+
+    let ucd = load_mini(&[], &["kMandarin", "kHanyuPinyin"]);
+    let unihan = &ucd.ud[0x5170].unihan;
+    assert_eq!(
+        unihan.keys().map(|x| &**x).collect::<Vec<_>>(),
+        ["kHanyuPinyin", "kMandarin"]
+    );
+    assert_eq!(&*unihan["kHanyuPinyin"], "10297.030:lán");
+
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("mini-unihan");
+    let _ = fs::remove_dir_all(&out);
+    fs::create_dir_all(&out).unwrap();
+    data::write_all(ucd, &out).unwrap();
+    assert!(out.join("data.uhman.bin").exists());
+    assert!(out.join("data.uhhanyupinyin.bin").exists());
+    assert!(!out.join("data.uhdef.bin").exists());
+
+    // and clients find them in data.info.json
+    let info = fs::read_to_string(out.join("data.info.json")).unwrap();
+    assert!(info.contains(
+        r#""unihanFields":[{"key":"kMandarin","file":"uhman"},{"key":"kHanyuPinyin","file":"uhhanyupinyin"}]"#
+    ));
+}
+
+#[test]
+fn bad_locale_codes() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/mini");
+    for code in ["../ja", "ja/x", "", "j", "ja_", "pt_BRAZILIAN"] {
+        let error = data::load(&dir, &[code], &data::DEFAULT_UNIHAN_FIELDS)
+            .err()
+            .expect("bad locale code was accepted");
+        assert_eq!(
+            format!("{:#}", error),
+            format!("bad locale code: {:?}", code)
+        );
+    }
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use data::names::Names;
+use data::Ucd;
+
+use crate::common::{mini, real};
+
+fn assert_round_trip(ucd: &Ucd) {
+    let names = ucd
+        .ud
+        .iter()
+        .flat_map(|x| x.name.as_deref())
+        .collect::<Vec<_>>();
+    let (encoded, offsets) = Names::encode(&names);
+
+    assert!(!names.is_empty());
+    for (name, offset) in names.into_iter().zip(offsets) {
+        assert_eq!(encoded.decode(offset).unwrap(), name);
+    }
+}
+
+#[test]
+fn every_name_round_trips() {
+    assert_round_trip(&mini());
+}
+
+#[test]
+fn every_real_name_round_trips() {
+    if let Some(ucd) = real() {
+        assert_round_trip(&ucd);
+    }
+}
+
+#[test]
+fn tokens() {
+    let (names, offsets) = Names::encode(&[
+        "LATIN SMALL LETTER A",
+        "HYPHEN-MINUS",
+        "TIBETAN MARK TSA -PHRU",
+        "LATIN SMALL LETTER B",
+        "TIBETAN MARK BKA- SHOG YIG MGO",
+        "LATIN SMALL LETTER A",
+    ]);
+
+    // most frequent first, then in code point order
+    assert_eq!(
+        names.tokens,
+        [
+            "LATIN", "LETTER", "MARK", "SMALL", "TIBETAN", "", "-", "A", "B", "BKA-", "HYPHEN-",
+            "MGO", "MINUS", "PHRU", "SHOG", "TSA", "YIG",
+        ]
+    );
+    assert_eq!(offsets, [0, 5, 8, 14, 19, 0]);
+    assert_eq!(names.data[..5], [4, 0, 3, 1, 7]);
+    assert_eq!(names.decode(8).unwrap(), "TIBETAN MARK TSA -PHRU");
+    assert_eq!(names.decode(19).unwrap(), "TIBETAN MARK BKA- SHOG YIG MGO");
+}
+
+#[test]
+fn token_indices_past_127_take_more_bytes() {
+    let names = (0..200)
+        .map(|i| format!("WORD{:03}", i))
+        .collect::<Vec<_>>();
+    let names = names.iter().map(|x| &**x).collect::<Vec<_>>();
+    let (encoded, offsets) = Names::encode(&names);
+
+    assert_eq!(offsets[127..130], [254, 256, 259]);
+    assert_eq!(encoded.data[256..259], [1, 0x81, 0x00]);
+    for (name, offset) in names.into_iter().zip(offsets) {
+        assert_eq!(encoded.decode(offset).unwrap(), name);
+    }
+}
+
+#[test]
+fn corruption_is_detected() {
+    let (mut names, _) = Names::encode(&["LATIN SMALL LETTER A"]);
+
+    assert_eq!(
+        names.decode(9).unwrap_err().to_string(),
+        "name offset out of bounds"
+    );
+    assert!(names.decode(5).is_err());
+
+    names.tokens.pop();
+    assert_eq!(
+        names.decode(0).unwrap_err().to_string(),
+        "name token out of bounds"
+    );
+}
@@ -0,0 +1,34 @@
+use data::pinyin::pinyin_keys;
+
+#[test]
+fn tone_marks() {
+    assert_eq!(pinyin_keys("lán"), ["lan2", "lan"]);
+    assert_eq!(pinyin_keys("xìng"), ["xing4", "xing"]);
+    assert_eq!(pinyin_keys("mā"), ["ma1", "ma"]);
+    assert_eq!(pinyin_keys("ǎi"), ["ai3", "ai"]);
+
+    // syllables with no precomposed form use combining marks
+    assert_eq!(pinyin_keys("m\u{0304}"), ["m1", "m"]);
+    assert_eq!(pinyin_keys("ê\u{0304}"), ["e1", "e"]);
+}
+
+#[test]
+fn toneless() {
+    // neutral tone syllables have no numbered key
+    assert_eq!(pinyin_keys("ma"), ["ma"]);
+    assert_eq!(pinyin_keys("de"), ["de"]);
+}
+
+#[test]
+fn u_with_diaeresis() {
+    assert_eq!(pinyin_keys("lǜ"), ["lv4", "lv", "lu4", "lu"]);
+    assert_eq!(pinyin_keys("nǚ"), ["nv3", "nv", "nu3", "nu"]);
+    assert_eq!(pinyin_keys("lü"), ["lv", "lu"]);
+}
+
+#[test]
+fn multiple_readings() {
+    assert_eq!(pinyin_keys("lán lǎn"), ["lan2", "lan", "lan3"]);
+    assert_eq!(pinyin_keys("lǜ lù"), ["lv4", "lv", "lu4", "lu"]);
+    assert_eq!(pinyin_keys(""), Vec::<String>::new());
+}
@@ -0,0 +1,695 @@
+mod common;
+
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use data::names::Names;
+use data::strings::read_strings;
+use data::{DEFAULT_UNIHAN_FIELDS, RESIDUAL_OFFSET};
+use serde::Deserialize;
+
+use crate::common::{load_mini, mini};
+
+// Pages of sparse files that are dumped in full. Other pages are only
+// summarised, since the fixtures fill whole pages with ideographs.
+const PAGES: [usize; 13] = [
+    0x00, 0x03, 0x20, 0x26, 0x51, 0x9F, 0xD4, 0xD7, 0xF9, 0xFE, 0x1F3, 0x1F4, 0x1F6,
+];
+
+// Compares each output file with a human-readable snapshot in
+// tests/snapshots/mini. Run with UPDATE_SNAPSHOTS=1 to accept changes.
+#[test]
+fn mini_outputs() {
+    let out = Path::new(env!("CARGO_TARGET_TMPDIR")).join("mini");
+    let snapshots = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots/mini");
+    let _ = fs::remove_dir_all(&out);
+    fs::create_dir_all(&out).unwrap();
+    data::write_all(mini(), &out).unwrap();
+
+    let outputs = Outputs::read(&out);
+    let actual = outputs
+        .files
+        .keys()
+        .map(|name| (format!("{}.txt", name), outputs.snapshot(name)))
+        .collect::<BTreeMap<_, _>>();
+    let expected = read_dir(&snapshots)
+        .into_iter()
+        .map(|(name, content)| (name, String::from_utf8(content).unwrap()))
+        .collect::<BTreeMap<_, _>>();
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        let _ = fs::remove_dir_all(&snapshots);
+        fs::create_dir_all(&snapshots).unwrap();
+        for (name, content) in &actual {
+            fs::write(snapshots.join(name), content).unwrap();
+        }
+        return;
+    }
+
+    let mut report = String::new();
+    for name in expected.keys().filter(|x| !actual.contains_key(*x)) {
+        writeln!(report, "{}: no longer written", name).unwrap();
+    }
+    for (name, actual) in &actual {
+        let Some(expected) = expected.get(name) else {
+            writeln!(report, "{}: missing snapshot", name).unwrap();
+            continue;
+        };
+        let expected = expected.lines().collect::<Vec<_>>();
+        let actual = actual.lines().collect::<Vec<_>>();
+        let changed = (0..expected.len().max(actual.len()))
+            .filter(|&i| expected.get(i) != actual.get(i))
+            .collect::<Vec<_>>();
+        for &i in changed.iter().take(10) {
+            writeln!(report, "{}:{}", name, i + 1).unwrap();
+            writeln!(report, "-{}", expected.get(i).unwrap_or(&"")).unwrap();
+            writeln!(report, "+{}", actual.get(i).unwrap_or(&"")).unwrap();
+        }
+        if changed.len() > 10 {
+            writeln!(report, "{}: {} more lines", name, changed.len() - 10).unwrap();
+        }
+    }
+    assert!(
+        report.is_empty(),
+        "outputs differ from snapshots (UPDATE_SNAPSHOTS=1 to accept)\n{}",
+        report
+    );
+}
+
+// Sequences and pools are built from hash maps in places, so check that
+// their order doesn’t depend on hashing.
+#[test]
+fn mini_outputs_are_deterministic() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (first, second) = (dir.join("mini-first"), dir.join("mini-second"));
+    for dir in [&first, &second] {
+        let _ = fs::remove_dir_all(dir);
+        fs::create_dir_all(dir).unwrap();
+        data::write_all(mini(), dir).unwrap();
+    }
+
+    assert!(read_dir(&first) == read_dir(&second));
+}
+
+// Each locale only adds files of its own ($LOCALES in main.rs).
+#[test]
+fn locales_do_not_change_main_outputs() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let (without, with) = (dir.join("mini-no-locales"), dir.join("mini-locales"));
+    for dir in [&without, &with] {
+        let _ = fs::remove_dir_all(dir);
+        fs::create_dir_all(dir).unwrap();
+    }
+    data::write_all(mini(), &without).unwrap();
+    data::write_all(load_mini(&["de"], &DEFAULT_UNIHAN_FIELDS), &with).unwrap();
+
+    let mut with = read_dir(&with);
+    let locale = with
+        .keys()
+        .filter(|x| x.starts_with("data.de."))
+        .cloned()
+        .collect::<Vec<_>>();
+    assert!(locale.contains(&"data.de.seqa.bin".to_owned()));
+    for name in locale {
+        with.remove(&name);
+    }
+    let without = read_dir(&without);
+    assert_eq!(
+        with.keys().collect::<Vec<_>>(),
+        without.keys().collect::<Vec<_>>()
+    );
+
+    // except that the locale’s files are listed in these
+    let unlisted = |name: &str, bytes: &[u8]| -> Vec<u8> {
+        match name {
+            "data.info.json" => {
+                let mut info: serde_json::Value = serde_json::from_slice(bytes).unwrap();
+                let widths = info["indexWidths"].as_object_mut().unwrap();
+                widths.retain(|x, _| !x.starts_with("data.de."));
+                serde_json::to_vec(&info).unwrap()
+            }
+            "schema.ts" => String::from_utf8(bytes.to_owned())
+                .unwrap()
+                .lines()
+                .filter(|x| !x.contains("\"data.de."))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .into_bytes(),
+            _ => bytes.to_owned(),
+        }
+    };
+    for (name, bytes) in &without {
+        assert!(
+            unlisted(name, &with[name]) == unlisted(name, bytes),
+            "{} differs",
+            name
+        );
+    }
+}
+
+fn read_dir(dir: &Path) -> BTreeMap<String, Vec<u8>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return BTreeMap::default();
+    };
+
+    entries
+        .map(|entry| {
+            let path = entry.unwrap().path();
+            let name = path.file_name().unwrap().to_str().unwrap().to_owned();
+            (name, fs::read(path).unwrap())
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DataInfo {
+    sequence_bucket_count: usize,
+    sequence_count: usize,
+    radical_stroke_group_count: usize,
+    index_widths: BTreeMap<String, usize>,
+}
+
+struct Outputs {
+    files: BTreeMap<String, Vec<u8>>,
+    info: DataInfo,
+    strings: Vec<String>,
+    nl_strings: Vec<String>,
+    names: Names,
+    sequences: Vec<String>,
+}
+
+impl Outputs {
+    fn read(dir: &Path) -> Self {
+        let files = read_dir(dir);
+        let info = serde_json::from_slice(&files["data.info.json"]).unwrap();
+        let strings = read_strings(&files["data.string.bin"]).unwrap();
+        let nl_strings = read_strings(&files["data.nl.string.bin"]).unwrap();
+        let names = Names {
+            tokens: read_strings(&files["data.nametok.bin"]).unwrap(),
+            data: files["data.nameseq.bin"].clone(),
+        };
+        let mut result = Self {
+            files,
+            info,
+            strings,
+            nl_strings,
+            names,
+            sequences: vec![],
+        };
+
+        // seqp: for each sequence: start (u32), len (u8), then the points
+        let mut reader = result.reader("data.seqp.bin");
+        let ranges = (0..result.info.sequence_count)
+            .map(|_| (reader.u32() as usize, reader.u8() as usize))
+            .collect::<Vec<_>>();
+        let points = reader.rest(4, |x| x.u32());
+        result.sequences = ranges
+            .into_iter()
+            .map(|(start, len)| points_list(&points[start..][..len]))
+            .collect();
+
+        result
+    }
+
+    fn reader(&self, name: &str) -> Reader<'_> {
+        Reader {
+            bytes: &self.files[name],
+            offset: 0,
+        }
+    }
+
+    fn snapshot(&self, name: &str) -> String {
+        let bytes = &self.files[name];
+        let mut result = format!(
+            "{}: {} bytes, fnv1a64 {:016x}\n",
+            name,
+            bytes.len(),
+            fnv1a64(bytes)
+        );
+        let strings: Label = &|x| format!("{:?}", self.strings[x as usize]);
+        let nl_strings: Label = &|x| format!("{:?}", self.nl_strings[x as usize]);
+        let names: Label = &|x| format!("{:?}", self.names.decode(x as usize).unwrap());
+        let (strings, nl_strings, names) = (Some(strings), Some(nl_strings), Some(names));
+
+        let body = match name {
+            "egcbreak.ts" | "schema.ts" | "data.info.json" => {
+                String::from_utf8(bytes.clone()).unwrap()
+            }
+            "data.string.bin" => self.pool(&self.strings),
+            "data.nl.string.bin" => self.pool(&self.nl_strings),
+            "data.nametok.bin" => self.pool(&self.names.tokens),
+            "data.nameseq.bin" => self.name_sequences(),
+            "data.name.bin" => self.sparse(name, None, names),
+            "data.bits.bin" | "data.ebits.bin" | "data.gb.bin" | "data.estatus.bin"
+            | "data.uhts.bin" | "data.aliasc.bin" | "data.varc.bin" | "data.nl.notec.bin"
+            | "data.nl.xrefc.bin" | "data.hlvt.bin" | "data.uhrs.bin" => {
+                self.sparse(name, Some(0), None)
+            }
+            "data.eorder.bin" => self.sparse(name, Some(0xFFFF), None),
+            "data.equal.bin" | "data.aliasi.bin" | "data.vari.bin" | "data.nl.notei.bin"
+            | "data.nl.xrefi.bin" => self.sparse(name, None, None),
+            "data.aliass.bin" => self.list("data.alias", "s", strings),
+            "data.aliast.bin" => self.list("data.alias", "t", None),
+            "data.varp.bin" => self.list("data.var", "p", None),
+            "data.vart.bin" => self.list("data.var", "t", None),
+            "data.nl.notes.bin" => self.list("data.nl.note", "s", nl_strings),
+            "data.nl.notet.bin" => self.list("data.nl.note", "t", None),
+            "data.nl.xrefp.bin" => self.list("data.nl.xref", "p", None),
+            "data.nl.subheader.bin" => self.subheaders(),
+            "data.pagebits.bin" => self.pagebits(),
+            "data.pyk.bin" | "data.pyi.bin" | "data.pyp.bin" => self.pinyin(name),
+            "data.radical.bin" => self.radicals(),
+            "data.rsindex.bin" => self.radical_stroke_index(),
+            "data.seqb.bin" => self.sequence_buckets(),
+            "data.seqe.bin" => self.sequence_emoji_test(),
+            "data.seqk.bin" => self.sequence_bits(),
+            "data.seqp.bin" => self.sequences.iter().fold(String::new(), |mut a, x| {
+                writeln!(a, "{}", x).unwrap();
+                a
+            }),
+            "data.seqa.bin" | "data.seqn.bin" => self.sequence_lists(name),
+            "data.dnrp.bin" | "data.gc.bin" | "data.block.bin" | "data.age.bin"
+            | "data.hjsn.bin" | "data.egroup.bin" | "data.esubgroup.bin" | "data.eversion.bin"
+            | "data.uhdef.bin" | "data.uhman.bin" | "data.uhcan.bin" | "data.uhjpn.bin"
+            | "data.uhjon.bin" | "data.uhjkun.bin" | "data.uhkor.bin" | "data.uhhang.bin"
+            | "data.uhviet.bin" => self.sparse(name, None, strings),
+            _ => panic!("no snapshot format for {}", name),
+        };
+        result.push_str(&body);
+
+        result
+    }
+
+    fn pool(&self, strings: &[String]) -> String {
+        let mut result = String::new();
+        for (i, string) in strings.iter().enumerate() {
+            writeln!(result, "{}\t{:?}", i, string).unwrap();
+        }
+
+        result
+    }
+
+    // Width of the indices in a flat or record file (see data.info.json).
+    fn index_width(&self, name: &str) -> usize {
+        self.info.index_widths[name]
+    }
+
+    // Summarises each page, then dumps the values in PAGES. Index files
+    // have no default, so absent points read as all ones.
+    fn sparse(&self, name: &str, default: Option<u32>, label: Option<Label>) -> String {
+        let width = usize::from(self.files[name][0]);
+        let default = default.unwrap_or_else(|| no_index(width));
+        let (layout, pages) = sparse_pages(&self.files[name], default);
+        let mut result = format!(
+            "u{}, {}, {} of 4352 pages\n",
+            width * 8,
+            layout,
+            pages.len()
+        );
+
+        for (page, values) in &pages {
+            let count = values.iter().filter(|&&x| x != default).count();
+            let bytes = values
+                .iter()
+                .flat_map(|x| x.to_be_bytes())
+                .collect::<Vec<_>>();
+            writeln!(
+                result,
+                "page {:04X}: {} values, fnv1a64 {:016x}",
+                page,
+                count,
+                fnv1a64(&bytes)
+            )
+            .unwrap();
+        }
+        for (page, values) in pages.iter().filter(|(x, _)| PAGES.contains(x)) {
+            for (i, &value) in values.iter().enumerate() {
+                if value != default {
+                    write!(result, "U+{:04X}\t{}", page * 256 + i, value).unwrap();
+                    if let Some(label) = label {
+                        write!(result, "\t{}", label(value)).unwrap();
+                    }
+                    result.push('\n');
+                }
+            }
+        }
+
+        result
+    }
+
+    // Dumps a list file (see write_list_files) with the point that owns
+    // each item, as given by the counts and indices.
+    fn list(&self, stem: &str, suffix: &str, label: Option<Label>) -> String {
+        let (_, counts) = sparse_pages(&self.files[&format!("{}c.bin", stem)], 0);
+        let mut owners = Vec::default();
+        for (page, values) in counts {
+            for (i, count) in values.into_iter().enumerate() {
+                owners.extend((0..count).map(|_| page * 256 + i));
+            }
+        }
+
+        let name = format!("{}{}.bin", stem, suffix);
+        let width = match suffix {
+            "s" => self.index_width(&name),
+            "p" => 4,
+            _ => 1,
+        };
+        let mut reader = self.reader(&name);
+        let values = reader.rest(width, |x| x.uint(width));
+        assert_eq!(values.len(), owners.len());
+
+        let mut result = String::new();
+        for (owner, value) in owners.into_iter().zip(values) {
+            write!(result, "U+{:04X}\t", owner).unwrap();
+            match (suffix, label) {
+                ("p", _) => write!(result, "U+{:04X}", value).unwrap(),
+                (_, Some(label)) => write!(result, "{}", label(value)).unwrap(),
+                _ => write!(result, "{}", value).unwrap(),
+            }
+            result.push('\n');
+        }
+
+        result
+    }
+
+    // Dumps each distinct name with its offset and token indices.
+    fn name_sequences(&self) -> String {
+        let mut reader = self.reader("data.nameseq.bin");
+        let mut result = String::new();
+
+        while !reader.is_empty() {
+            let offset = reader.offset;
+            let tokens = (0..reader.u8())
+                .map(|_| {
+                    let mut token = 0;
+                    loop {
+                        let byte = reader.u8();
+                        token = (token << 7) | u32::from(byte & 0x7F);
+                        if byte & 0x80 == 0 {
+                            break token;
+                        }
+                    }
+                })
+                .collect::<Vec<_>>();
+            let name = self.names.decode(offset).unwrap();
+            writeln!(result, "{}\t{:?}\t{:?}", offset, name, tokens).unwrap();
+        }
+
+        result
+    }
+
+    fn subheaders(&self) -> String {
+        let mut reader = self.reader("data.nl.subheader.bin");
+        let width = self.index_width("data.nl.subheader.bin");
+        let mut result = String::new();
+
+        while !reader.is_empty() {
+            let (first, last) = (reader.u32(), reader.u32());
+            let name = &self.nl_strings[reader.uint(width) as usize];
+            writeln!(result, "U+{:04X}..U+{:04X}\t{:?}", first, last, name).unwrap();
+        }
+
+        result
+    }
+
+    fn pagebits(&self) -> String {
+        let mut reader = self.reader("data.pagebits.bin");
+        let mut result = String::new();
+
+        for page in 0..0x1100 {
+            let bits = reader.u8();
+            if bits != 0 {
+                writeln!(result, "page {:04X}\t{:04b}", page, bits).unwrap();
+            }
+        }
+        assert!(reader.is_empty());
+
+        result
+    }
+
+    fn pinyin(&self, name: &str) -> String {
+        if name != "data.pyk.bin" {
+            return "see data.pyk.bin.txt\n".to_owned();
+        }
+
+        let keys: Vec<String> = serde_json::from_slice(&self.files["data.pyk.bin"]).unwrap();
+        let mut index = self.reader("data.pyi.bin");
+        let points = self.reader("data.pyp.bin").rest(4, |x| x.u32());
+        let mut result = String::new();
+
+        for key in keys {
+            let (start, len) = (index.u32() as usize, index.u32() as usize);
+            writeln!(result, "{}\t{}", key, points_list(&points[start..][..len])).unwrap();
+        }
+        assert!(index.is_empty());
+
+        result
+    }
+
+    fn radicals(&self) -> String {
+        let mut reader = self.reader("data.radical.bin");
+        let mut result = String::new();
+
+        while !reader.is_empty() {
+            let (radical, simplified) = (reader.u8(), reader.u8());
+            let (character, ideograph) = (reader.u32(), reader.u32());
+            writeln!(
+                result,
+                "{}{}\tU+{:04X}\tU+{:04X}",
+                radical,
+                "'".repeat(simplified.into()),
+                character,
+                ideograph
+            )
+            .unwrap();
+        }
+
+        result
+    }
+
+    fn radical_stroke_index(&self) -> String {
+        let mut reader = self.reader("data.rsindex.bin");
+        let groups = (0..self.info.radical_stroke_group_count)
+            .map(|_| {
+                let key = (reader.u8(), reader.u8(), reader.u8());
+                (key, reader.u32() as usize, reader.u32() as usize)
+            })
+            .collect::<Vec<_>>();
+        let points = reader.rest(4, |x| x.u32());
+        let mut result = String::new();
+
+        for ((radical, simplified, residual), start, len) in groups {
+            let points = points_list(&points[start..][..len]);
+            let simplified = "'".repeat(simplified.into());
+            let residual = isize::from(residual) - RESIDUAL_OFFSET;
+            writeln!(result, "{}{}.{}\t{}", radical, simplified, residual, points).unwrap();
+        }
+
+        result
+    }
+
+    fn sequence_buckets(&self) -> String {
+        let mut reader = self.reader("data.seqb.bin");
+        let width = self.index_width("data.seqb.bin");
+        let mut result = String::new();
+
+        for _ in 0..self.info.sequence_bucket_count {
+            let key = points_list(&[reader.u32(), reader.u32()]);
+            let (start, len) = (reader.uint(width), reader.u8());
+            writeln!(result, "{}\t{}\t{}", key, start, len).unwrap();
+        }
+        assert!(reader.is_empty());
+
+        result
+    }
+
+    fn sequence_bits(&self) -> String {
+        let mut reader = self.reader("data.seqk.bin");
+        let mut result = String::new();
+
+        for sequence in &self.sequences {
+            writeln!(result, "{}\t{:07b}", sequence, reader.u8()).unwrap();
+        }
+        assert!(reader.is_empty());
+
+        result
+    }
+
+    fn sequence_emoji_test(&self) -> String {
+        let mut reader = self.reader("data.seqe.bin");
+        let width = self.index_width("data.seqe.bin");
+        let mut result = String::new();
+
+        for sequence in &self.sequences {
+            let order = reader.u16();
+            let strings = [reader.uint(width), reader.uint(width), reader.uint(width)];
+            let (qualified, status) = (reader.uint(width), reader.u8());
+            if order == 0xFFFF {
+                continue;
+            }
+            write!(result, "{}\t{}", sequence, order).unwrap();
+            for string in strings {
+                write!(result, "\t{:?}", self.strings[string as usize]).unwrap();
+            }
+            if qualified != no_index(width) {
+                write!(result, "\t{}", self.sequences[qualified as usize]).unwrap();
+            }
+            writeln!(result, "\t{}", status).unwrap();
+        }
+        assert!(reader.is_empty());
+
+        result
+    }
+
+    // seqa and seqn: for each sequence: start (u32), len (u8), then the
+    // strings (string indices), then the alias types or provisional
+    // flags (u8)
+    fn sequence_lists(&self, name: &str) -> String {
+        let mut reader = self.reader(name);
+        let width = self.index_width(name);
+        let ranges = (0..self.info.sequence_count)
+            .map(|_| (reader.u32() as usize, reader.u8() as usize))
+            .collect::<Vec<_>>();
+        let total = ranges.iter().map(|&(_, len)| len).sum::<usize>();
+        let strings = (0..total).map(|_| reader.uint(width)).collect::<Vec<_>>();
+        let types = reader.rest(1, |x| x.u8());
+        assert_eq!(types.len(), total);
+
+        let mut result = String::new();
+        for (sequence, (start, len)) in self.sequences.iter().zip(ranges) {
+            for i in start..(start + len) {
+                writeln!(
+                    result,
+                    "{}\t{:?}\t{}",
+                    sequence, self.strings[strings[i] as usize], types[i]
+                )
+                .unwrap();
+            }
+        }
+
+        result
+    }
+}
+
+// Describes a value, such as by looking up a string index.
+type Label<'l> = &'l dyn Fn(u32) -> String;
+
+struct Reader<'b> {
+    bytes: &'b [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    fn uint(&mut self, width: usize) -> u32 {
+        let bytes = &self.bytes[self.offset..][..width];
+        self.offset += width;
+
+        bytes.iter().fold(0, |a, &x| (a << 8) | u32::from(x))
+    }
+
+    fn u8(&mut self) -> u8 {
+        self.uint(1).try_into().unwrap()
+    }
+
+    fn u16(&mut self) -> u16 {
+        self.uint(2).try_into().unwrap()
+    }
+
+    fn u32(&mut self) -> u32 {
+        self.uint(4)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset == self.bytes.len()
+    }
+
+    fn rest<T>(&mut self, width: usize, mut read: impl FnMut(&mut Self) -> T) -> Vec<T> {
+        assert_eq!((self.bytes.len() - self.offset) % width, 0);
+        let mut result = Vec::default();
+        while !self.is_empty() {
+            result.push(read(self));
+        }
+
+        result
+    }
+}
+
+// Decodes a trie (see trie.rs) into its layout, and the values in each
+// 256-point page where any point is in a stored data block.
+fn sparse_pages(bytes: &[u8], default: u32) -> (String, Vec<(usize, Vec<u32>)>) {
+    let mut reader = Reader { bytes, offset: 0 };
+    let width = usize::from(reader.u8());
+    let stages = reader.u8();
+    let (index_bits, data_bits) = (reader.u8(), reader.u8());
+    let (index_blocks, data_blocks) = (usize::from(reader.u16()), usize::from(reader.u16()));
+    let shift = index_bits + data_bits;
+    assert_eq!(stages, if index_bits > 0 { 3 } else { 2 });
+
+    let first = (0..(0x110000 >> shift))
+        .map(|_| reader.u16())
+        .collect::<Vec<_>>();
+    let index = (0..(index_blocks << index_bits))
+        .map(|_| reader.u16())
+        .collect::<Vec<_>>();
+    let data = reader.rest(width, |x| x.uint(width));
+    assert_eq!(data.len(), data_blocks << data_bits);
+
+    let get = |point: usize| {
+        let mut block = first[point >> shift];
+        if index_bits > 0 && block != 0xFFFF {
+            let i = (point >> data_bits) & ((1 << index_bits) - 1);
+            block = index[(usize::from(block) << index_bits) + i];
+        }
+        (block != 0xFFFF)
+            .then(|| data[(usize::from(block) << data_bits) + (point & ((1 << data_bits) - 1))])
+    };
+    let layout = format!(
+        "{}-stage trie, {}/{}/{} bits, {} index blocks, {} data blocks",
+        stages,
+        21 - shift,
+        index_bits,
+        data_bits,
+        index_blocks,
+        data_blocks
+    );
+    let pages = (0..0x1100)
+        .map(|page| {
+            (
+                page,
+                (0..256).map(|i| get(page * 256 + i)).collect::<Vec<_>>(),
+            )
+        })
+        .filter(|(_, values)| values.iter().any(|x| x.is_some()))
+        .map(|(page, values)| {
+            (
+                page,
+                values.into_iter().map(|x| x.unwrap_or(default)).collect(),
+            )
+        })
+        .collect();
+
+    (layout, pages)
+}
+
+fn no_index(width: usize) -> u32 {
+    ((1u64 << (8 * width)) - 1) as u32
+}
+
+fn points_list(points: &[u32]) -> String {
+    points
+        .iter()
+        .map(|x| format!("U+{:04X}", x))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |a, &x| {
+        (a ^ u64::from(x)).wrapping_mul(0x100000001b3)
+    })
+}
@@ -0,0 +1,44 @@
+use data::strings::{read_strings, write_strings};
+
+#[test]
+fn round_trip() {
+    let strings = ["", "LATIN", "Control (Cc)", "ꙮ", "🐈 cat"];
+    let mut bytes = vec![];
+    write_strings(&mut bytes, &strings).unwrap();
+
+    assert_eq!(bytes[..8], [0, 0, 0, 5, 0, 0, 0, 0]);
+    assert_eq!(bytes[24..28], [0, 0, 0, 28]);
+    assert_eq!(bytes.len(), 4 + 6 * 4 + 28);
+    assert_eq!(read_strings(&bytes).unwrap(), strings);
+}
+
+#[test]
+fn empty() {
+    let mut bytes = vec![];
+    write_strings::<&str>(&mut bytes, &[]).unwrap();
+
+    assert_eq!(bytes, [0, 0, 0, 0, 0, 0, 0, 0]);
+    assert!(read_strings(&bytes).unwrap().is_empty());
+}
+
+#[test]
+fn corruption_is_detected() {
+    let mut bytes = vec![];
+    write_strings(&mut bytes, &["NULL", "DELETE"]).unwrap();
+    let error = |bytes: &[u8]| read_strings(bytes).unwrap_err().to_string();
+
+    assert_eq!(
+        error(&bytes[..bytes.len() - 1]),
+        "string pool length mismatch"
+    );
+
+    let mut overlong = bytes.clone();
+    overlong[11] = 12;
+    assert_eq!(error(&overlong), "string out of bounds");
+
+    let mut invalid = bytes.clone();
+    invalid[16] = 0xFF;
+    assert!(read_strings(&invalid).is_err());
+
+    assert!(read_strings(&bytes[..6]).is_err());
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use data::derived_name;
+use data::details::AliasType::*;
+use data::details::{Bits, Details, GraphemeBreak, HangulSyllableType};
+use data::Ucd;
+
+use crate::common::real;
+
+// Leave out the editorial content from NamesList.txt, which is revised
+// far more often than the properties checked here.
+fn without_names_list(ucd: &Ucd, point: usize) -> Details {
+    let mut result = ucd.ud[point].clone();
+    result.alias.retain(|x| x.r#type != Informal);
+    result.nlnote.clear();
+    result.nlxref.clear();
+    result
+}
+
+// Only check kDefinition and kMandarin, since other Unihan fields are
+// more likely to change from version to version.
+fn with_core_unihan(ucd: &Ucd, point: usize) -> Details {
+    let mut result = without_names_list(ucd, point);
+    result
+        .unihan
+        .retain(|x, _| &**x == "kDefinition" || &**x == "kMandarin");
+    result.uhrs = None;
+    result.uhts = None;
+    result.variant.clear();
+    result
+}
+
+#[test]
+fn control_characters() {
+    let Some(ucd) = real() else { return };
+
+    assert_eq!(
+        without_names_list(&ucd, 0x0000),
+        Details::builder()
+            .alias(&[("NULL", Unicode1), ("NULL", Control), ("NUL", Abbreviation)])
+            .gb(GraphemeBreak::Control)
+            .gc("Control (Cc)")
+            .block("Basic Latin")
+            .age("Unicode 1.1")
+            .build(),
+    );
+    assert_eq!(
+        without_names_list(&ucd, 0x000A),
+        Details::builder()
+            .alias(&[
+                ("LINE FEED (LF)", Unicode1),
+                ("LINE FEED", Control),
+                ("NEW LINE", Control),
+                ("END OF LINE", Control),
+                ("LF", Abbreviation),
+                ("NL", Abbreviation),
+                ("EOL", Abbreviation),
+            ])
+            .gb(GraphemeBreak::Lf)
+            .gc("Control (Cc)")
+            .block("Basic Latin")
+            .age("Unicode 1.1")
+            .build()
+    );
+    assert_eq!(
+        without_names_list(&ucd, 0x0080),
+        Details::builder()
+            .alias(&[("PADDING CHARACTER", Figment), ("PAD", Abbreviation)])
+            .gb(GraphemeBreak::Control)
+            .gc("Control (Cc)")
+            .block("Latin-1 Supplement")
+            .age("Unicode 1.1")
+            .build()
+    );
+}
+
+#[test]
+fn names_and_corrections() {
+    let Some(ucd) = real() else { return };
+
+    assert_eq!(
+        without_names_list(&ucd, 0x039B),
+        Details::builder()
+            .name("GREEK CAPITAL LETTER LAMDA")
+            .alias(&[("GREEK CAPITAL LETTER LAMBDA", Unicode1)])
+            .gc("Uppercase Letter (Lu)")
+            .block("Greek and Coptic")
+            .age("Unicode 1.1")
+            .build(),
+    );
+    assert_eq!(
+        without_names_list(&ucd, 0xFE18),
+        Details::builder()
+            .name("PRESENTATION FORM FOR VERTICAL RIGHT WHITE LENTICULAR BRAKCET")
+            .alias(&[(
+                "PRESENTATION FORM FOR VERTICAL RIGHT WHITE LENTICULAR BRACKET",
+                Correction,
+            )])
+            .gc("Close Punctuation (Pe)")
+            .block("Vertical Forms")
+            .age("Unicode 4.1")
+            .build()
+    );
+    assert_eq!(
+        without_names_list(&ucd, 0xFEFF),
+        Details::builder()
+            .name("ZERO WIDTH NO-BREAK SPACE")
+            .alias(&[
+                ("BYTE ORDER MARK", Unicode1),
+                ("BYTE ORDER MARK", Alternate),
+                ("BOM", Abbreviation),
+                ("ZWNBSP", Abbreviation),
+            ])
+            .gb(GraphemeBreak::Control)
+            .gc("Format (Cf)")
+            .block("Arabic Presentation Forms-B")
+            .age("Unicode 1.1")
+            .build()
+    );
+}
+
+#[test]
+fn unihan() {
+    let Some(ucd) = real() else { return };
+
+    assert_eq!(
+        with_core_unihan(&ucd, 0x5170),
+        Details::builder()
+            .dnrp("CJK UNIFIED IDEOGRAPH-")
+            .gc("Other Letter (Lo)")
+            .block("CJK Unified Ideographs")
+            .age("Unicode 1.1")
+            .unihan(&[
+                ("kDefinition", "orchid; elegant, graceful"),
+                ("kMandarin", "lán"),
+            ])
+            .bits(Bits::KdefinitionExists | Bits::DerivedNameNr2)
+            .build()
+    );
+    assert_eq!(
+        with_core_unihan(&ucd, 0x9FFF),
+        Details::builder()
+            .dnrp("CJK UNIFIED IDEOGRAPH-")
+            .gc("Other Letter (Lo)")
+            .block("CJK Unified Ideographs")
+            .age("Unicode 14.0")
+            .unihan(&[("kMandarin", "xìng")])
+            .bits(Bits::DerivedNameNr2.into())
+            .build()
+    );
+    assert_eq!(
+        with_core_unihan(&ucd, 0xF900),
+        Details::builder()
+            .dnrp("CJK COMPATIBILITY IDEOGRAPH-")
+            .gc("Other Letter (Lo)")
+            .block("CJK Compatibility Ideographs")
+            .age("Unicode 1.1")
+            .unihan(&[("kDefinition", "how? what?")])
+            .bits(Bits::DerivedNameNr2 | Bits::KdefinitionExists)
+            .build()
+    );
+}
+
+#[test]
+fn hangul_syllables() {
+    let Some(ucd) = real() else { return };
+
+    assert_eq!(
+        without_names_list(&ucd, 0xD4DB),
+        Details::builder()
+            .dnrp("HANGUL SYLLABLE ")
+            .gb(GraphemeBreak::HangulLVT)
+            .gc("Other Letter (Lo)")
+            .block("Hangul Syllables")
+            .age("Unicode 2.0")
+            .hst(HangulSyllableType::Lvt)
+            .hlvt((17, 16, 15))
+            .bits(Bits::DerivedNameNr1.into())
+            .build()
+    );
+    assert_eq!(
+        without_names_list(&ucd, 0xD788),
+        Details::builder()
+            .dnrp("HANGUL SYLLABLE ")
+            .gb(GraphemeBreak::HangulLV)
+            .gc("Other Letter (Lo)")
+            .block("Hangul Syllables")
+            .age("Unicode 2.0")
+            .hst(HangulSyllableType::Lv)
+            .hlvt((18, 20, 0))
+            .bits(Bits::DerivedNameNr1.into())
+            .build()
+    );
+    assert_eq!(
+        derived_name(&ucd.ud, &ucd.name_rules, 0xD4DB).as_deref(),
+        Some("HANGUL SYLLABLE PWILH")
+    );
+    assert_eq!(
+        derived_name(&ucd.ud, &ucd.name_rules, 0xD788).as_deref(),
+        Some("HANGUL SYLLABLE HI")
+    );
+}
@@ -1,22 +1,33 @@
 #!/bin/sh
-# usage: ( cd data; ./update.sh )
+# usage: ( cd data; ./update.sh [dir] )
+# Downloads the UCD into dir, or by default into the data directory, which
+# replaces the files that are checked in (see “how to update unicode”).
 set -eu
 
 unicode_ver=16.0.0
 emoji_ver=16.0
 
+mkdir -p "${1:-.}"
+cd "${1:-.}"
+
 for i in \
     Blocks.txt \
+    CJKRadicals.txt \
     DerivedAge.txt \
     HangulSyllableType.txt \
     Jamo.txt \
     NameAliases.txt \
     NamedSequences.txt \
+    NamedSequencesProv.txt \
+    NamesList.txt \
     PropertyValueAliases.txt \
+    StandardizedVariants.txt \
     UnicodeData.txt \
     auxiliary/GraphemeBreakProperty.txt \
     auxiliary/GraphemeBreakTest.txt \
     emoji/emoji-data.txt \
+    extracted/DerivedName.txt \
+    emoji/emoji-variation-sequences.txt \
     Unihan.zip \
 ; do
     echo $i
@@ -24,5 +35,5 @@ for i in \
 done
 echo 'emoji-test.txt (this may take a while)'
 curl -f\#O https://www.unicode.org/Public/emoji/${emoji_ver}/emoji-test.txt
-unzip -o Unihan.zip Unihan_Readings.txt
+unzip -o Unihan.zip Unihan_IRGSources.txt Unihan_Readings.txt Unihan_Variants.txt
 rm Unihan.zip
@@ -21,5 +21,14 @@ declare module "react-virtualized-auto-sizer" {
   export default class extends Component<AutoSizerProps> {}
 }
 
+// see UNIHAN_PATHS in fetch.ts
+interface NodeRequire {
+  context(
+    directory: string,
+    useSubdirectories: boolean,
+    regExp: RegExp,
+  ): { (request: string): any; keys(): string[] };
+}
+
 // see DefinePlugin in webpack.config.js
 declare const __COMMIT_HASH__: string;
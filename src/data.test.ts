@@ -13,10 +13,11 @@ import {
   hasDerivedNameNr2,
   getNextClusterBreak,
   getEmojiPresentationRuns,
+  toPinyinKey,
 } from "./data";
 import { pointsToString, stringToPoints } from "./encoding";
 import { pointsToYouPlus } from "./formatting";
-import { getData } from "./testing";
+import { getData, makeSparseWithDonkeyVote } from "./testing";
 
 test("getString returns correct string", () => {
   expect(getString(getData(), "gc", 0)).toBe("d");
@@ -29,6 +30,35 @@ test("getString returns null when index is out of bounds", () =>
 test("getString returns null when index is sentinel", () =>
   void expect(getString(getData(), "block", 1)).toBe(null));
 
+test("getString reads chunks that have been fetched", () => {
+  // 48-page chunks, where the last chunk (90) only covers 32 pages
+  const data = getData();
+  const chunk = (pages: number) =>
+    makeSparseWithDonkeyVote(
+      2,
+      2 * 2,
+      (result, start) => {
+        result.setUint16(start + 2 * 0, 3); // d
+        result.setUint16(start + 2 * 1, 4); // e
+      },
+      pages,
+    );
+  const chunks = Array<DataView | null | undefined>(91).fill(null);
+  chunks[1] = chunk(48);
+  chunks[2] = undefined;
+  chunks[90] = chunk(32);
+  data.gc = { chunkPoints: 0x3000, chunks };
+
+  expect(getString(data, "gc", 0x3000)).toBe("d");
+  expect(getString(data, "gc", 0x3001)).toBe("e");
+  expect(getString(data, "gc", 0x10e000)).toBe("d");
+  expect(getString(data, "gc", 0x10e001)).toBe("e");
+
+  // chunks with no values, or that haven’t been fetched yet
+  expect(getString(data, "gc", 0)).toBe(null);
+  expect(getString(data, "gc", 0x6000)).toBe(null);
+});
+
 test("getNameProperty returns correct value", () => {
   expect(getNameProperty(getData(), 0)).toBe("i0000");
   expect(getNameProperty(getData(), 1)).toBe("hxyz");
@@ -152,6 +182,14 @@ test("getEmojiPresentationRuns returns correct values", () => {
   }
 });
 
+test("toPinyinKey returns toneless and tone-numbered keys", () => {
+  expect(toPinyinKey("lan")).toBe("lan");
+  expect(toPinyinKey("lán")).toBe("lan2");
+  expect(toPinyinKey("LÁN")).toBe("lan2");
+  expect(toPinyinKey("lǜ")).toBe("lv4");
+  expect(toPinyinKey("lu:")).toBe("lv");
+});
+
 function bufferToDataView(buffer: Buffer): DataView {
   const array = new Uint8Array(buffer.byteLength);
   buffer.copy(array, 0, 0, buffer.byteLength);
@@ -1,58 +1,99 @@
 import { EGCBREAK } from "../data/egcbreak";
+import {
+  AliasType,
+  Bits,
+  DataFile,
+  EmojiBits,
+  EmojiStatus,
+  GraphemeBreak,
+  PageBits,
+  SequenceBits,
+  VariantType,
+} from "../data/schema";
 
 import { pointToYouPlus } from "./formatting";
 import { pointToString, stringToPoint, stringToPoints } from "./encoding";
 
+export { AliasType, EmojiStatus, GraphemeBreak, SequenceBits, VariantType };
+
 export type StringField =
   | "dnrp"
   | "gc"
   | "block"
   | "age"
   | "hjsn"
-  | "uhdef"
-  | "uhman";
+  | "egroup"
+  | "esubgroup"
+  | "eversion";
 
 export interface Data {
   info: DataInfo;
 
-  string: string[];
+  string: DataView;
+  nametok: string[];
 
-  bits: DataView;
-  ebits: DataView;
+  bits: Sparse;
+  ebits: Sparse;
   pagebits: DataView;
-  name: DataView;
-  aliasc: DataView;
-  aliasi: DataView;
+  name: Sparse;
+  nameseq: DataView;
+  aliasc: Sparse;
+  aliasi: Sparse;
   aliass: DataView;
   aliast: DataView;
-  dnrp: DataView;
-  gb: DataView;
-  gc: DataView;
-  block: DataView;
-  age: DataView;
-  hlvt: DataView;
-  hjsn: DataView;
-  uhdef: DataView;
-  uhman: DataView;
+  varc: Sparse;
+  vari: Sparse;
+  varp: DataView;
+  vart: DataView;
+  dnrp: Sparse;
+  gb: Sparse;
+  gc: Sparse;
+  block: Sparse;
+  age: Sparse;
+  hlvt: Sparse;
+  hjsn: Sparse;
+  // for each field in info.unihanFields, by key (like kMandarin)
+  unihan: { [key: string]: Sparse };
+  egroup: Sparse;
+  esubgroup: Sparse;
+  eversion: Sparse;
+  estatus: Sparse;
+  equal: Sparse;
 
   seqb: DataView;
   seqp: DataView;
   seqn: DataView;
+  seqa: DataView;
+  seqe: DataView;
+  seqk: DataView;
+
+  pyk: string[];
+  pyi: DataView;
+  pyp: DataView;
+}
+
+// A sparse file, which is a multi-stage trie (see data/src/trie.rs), or
+// chunks of it if the generator split it (see fetch.ts).
+export type Sparse = DataView | Chunked;
+
+// A sparse file split into chunks of chunkPoints points, each a trie for
+// those points. A chunk is null if none of its points have a value, or
+// undefined if it hasn’t been fetched yet.
+export interface Chunked {
+  chunkPoints: number;
+  chunks: (DataView | null | undefined)[];
 }
 
 export interface DataInfo {
   sequenceBucketCount: number;
   sequenceCount: number;
-}
-
-export enum AliasType {
-  Correction = 0,
-  Control = 1,
-  Alternate = 2,
-  Figment = 3,
-  Abbreviation = 4,
-  Unicode1 = 5,
-  Cldr = 6,
+  radicalStrokeGroupCount: number;
+  // width in bytes of the string or sequence indices in each flat or
+  // record file (sparse files record their width in their header)
+  indexWidths: { [file: string]: number };
+  // Unihan_Readings.txt fields that the generator kept, each in
+  // data.<file>.bin (see data/src/ur.rs)
+  unihanFields: { key: string; file: string }[];
 }
 
 export interface SequenceBucket {
@@ -60,62 +101,112 @@ export interface SequenceBucket {
   len: number;
 }
 
-export enum GraphemeBreak {
-  Cr = 1,
-  Lf = 2,
-  Control = 3,
-  Extend = 4,
-  Zwj = 5,
-  RegionalIndicator = 6,
-  Prepend = 7,
-  SpacingMark = 8,
-  HangulL = 9,
-  HangulV = 10,
-  HangulT = 11,
-  HangulLV = 12,
-  HangulLVT = 13,
-}
-
-// https://stackoverflow.com/q/51419176
-type KeyOfType<T, U> = { [P in keyof T]: T[P] extends U ? P : never }[keyof T];
-type SparseMemberType = {
-  method: KeyOfType<DataView, (_: number) => number>;
-  len: number;
-};
-const Uint8: SparseMemberType = { method: "getUint8", len: 1 };
-const Uint16: SparseMemberType = { method: "getUint16", len: 2 };
-
-function getSparse<T>(
-  ty: SparseMemberType,
-  field: DataView,
-  def: T,
+// An emoji-test.txt entry for a sequence (see data/src/et.rs).
+export interface SequenceEmojiTest {
+  group: string | null;
+  subgroup: string | null;
+  version: string | null;
+  qualified: number | null;
+  status: EmojiStatus;
+}
+
+// Reads a big-endian unsigned integer that is width bytes wide.
+function getUint(view: DataView, offset: number, width: number): number {
+  let result = 0;
+  for (let i = 0; i < width; i++)
+    result = result * 0x100 + view.getUint8(offset + i);
+  return result;
+}
+
+// Indices are u16, u24 or u32, where the all-ones value means none.
+function isNoIndex(index: number, width: number): boolean {
+  return index == 2 ** (8 * width) - 1;
+}
+
+function getIndexWidth(data: Data, file: DataFile): number {
+  return data.info.indexWidths[file] ?? 2;
+}
+
+/**
+ * Returns the trie for the given point in a sparse file, with the first
+ * point and number of points it covers, or null if the point is in a
+ * chunk with no values or one that hasn’t been fetched yet.
+ */
+function getTrie(
+  field: Sparse,
   point: number,
-): number | T {
-  const page_offset = field.getUint16(Math.floor(point / 256) * 2);
-  if (page_offset == 0xffff) return def;
+): [DataView, number, number] | null {
+  if (field instanceof DataView) return [field, 0, 0x110000];
+
+  const i = Math.floor(point / field.chunkPoints);
+  const chunk = field.chunks[i];
+  if (chunk == null) return null;
+
+  const first = i * field.chunkPoints;
+  return [chunk, first, Math.min(field.chunkPoints, 0x110000 - first)];
+}
+
+/**
+ * Returns the value for the given point in a multi-stage trie, or def
+ * if the point is in a block with no values (see data/src/trie.rs).
+ */
+function getSparse<T>(field: Sparse, def: T, point: number): number | T {
+  const trie = getTrie(field, point);
+  if (trie == null) return def;
+
+  const [view, first, len] = trie;
+  point -= first;
+  const width = view.getUint8(0);
+  const indexBits = view.getUint8(2);
+  const dataBits = view.getUint8(3);
+  const shift = indexBits + dataBits;
+  let offset = 8;
+
+  let block = view.getUint16(offset + (point >> shift) * 2);
+  if (block == 0xffff) return def;
+  offset += (len >> shift) * 2;
+
+  if (indexBits > 0) {
+    const index = (point >> dataBits) & ((1 << indexBits) - 1);
+    block = view.getUint16(offset + ((block << indexBits) + index) * 2);
+    if (block == 0xffff) return def;
+    offset += (view.getUint16(4) << indexBits) * 2;
+  }
+
+  const index = (block << dataBits) + (point & ((1 << dataBits) - 1));
+  return getUint(view, offset + index * width, width);
+}
 
-  const offset = 8704 + (page_offset * 256 + (point % 256)) * ty.len;
-  return field[ty.method](offset);
+/**
+ * Returns the index for the given point in a multi-stage trie of
+ * indices, or null if the point has none.
+ */
+function getSparseIndex(field: Sparse, point: number): number | null {
+  const index = getSparse(field, null, point);
+  if (index == null) return null;
+
+  const width = getTrie(field, point)![0].getUint8(0);
+  if (isNoIndex(index, width)) return null;
+  return index;
 }
 
-function getFlag(data: Data, shift: number, point: number): boolean {
-  return !!((getSparse(Uint8, data.bits, 0, point) >> shift) & 1);
+function getFlag(data: Data, shift: Bits, point: number): boolean {
+  return !!((getSparse(data.bits, 0, point) >> shift) & 1);
 }
 
-function getEmojiFlag(data: Data, shift: number, point: number): boolean {
-  return !!((getSparse(Uint8, data.ebits, 0, point) >> shift) & 1);
+function getEmojiFlag(data: Data, shift: EmojiBits, point: number): boolean {
+  return !!((getSparse(data.ebits, 0, point) >> shift) & 1);
 }
 
-function getPageFlag(data: Data, shift: number, page: number): boolean {
+function getPageFlag(data: Data, shift: PageBits, page: number): boolean {
   return !!((data.pagebits.getUint8(page) >> shift) & 1);
 }
 
 /**
  * Returns the string value for the given point and field.
  *
- * Do not use this function for the name field. For names, choose a
- * semantics appropriate for the context, then define a higher-level
- * function that wraps getString0.
+ * For names, choose a semantics appropriate for the context, then
+ * define a higher-level function that wraps getName.
  */
 export function getString(
   data: Data,
@@ -127,19 +218,72 @@ export function getString(
 
 function getString0(
   data: Data,
-  field: "name" | StringField,
+  field: StringField,
   point: number,
 ): string | null {
-  const index = getSparse(Uint16, data[field], 0xffff, point);
+  const index = getSparseIndex(data[field], point);
   return getStringByIndex(data, index);
 }
 
-function getStringByIndex(data: Data, index: number): string | null {
-  if (index == 0xffff || index >= data.string.length) {
+/**
+ * Returns the name for the given point, decoded from its tokens (see
+ * data/src/names.rs).
+ */
+function getName(data: Data, point: number): string | null {
+  let offset = getSparseIndex(data.name, point);
+  if (offset == null) return null;
+
+  let result = "";
+  let previous: string | null = null;
+  const count = data.nameseq.getUint8(offset++);
+  for (let i = 0; i < count; i++) {
+    let index = 0;
+    let byte;
+    do {
+      byte = data.nameseq.getUint8(offset++);
+      index = index * 0x80 + (byte & 0x7f);
+    } while (byte & 0x80);
+    const token = data.nametok[index];
+    if (previous != null && !previous.endsWith("-")) result += " ";
+    result += token;
+    previous = token;
+  }
+  return result;
+}
+
+function getStringByIndex(data: Data, index: number | null): string | null {
+  if (index == null || index >= getPoolLength(data.string)) {
     return null;
   }
 
-  return data.string[index];
+  return getPoolString(data.string, index);
+}
+
+const decoder = new TextDecoder();
+
+// Returns the number of strings in a binary string pool, where string i
+// is the UTF-8 between offsets i and i + 1 (see data/src/strings.rs).
+function getPoolLength(pool: DataView): number {
+  return pool.getUint32(0);
+}
+
+function getPoolString(pool: DataView, index: number): string {
+  const base = 4 + (getPoolLength(pool) + 1) * 4;
+  const start = pool.getUint32(4 + index * 4);
+  const end = pool.getUint32(4 + (index + 1) * 4);
+  const offset = pool.byteOffset + base + start;
+  return decoder.decode(new Uint8Array(pool.buffer, offset, end - start));
+}
+
+/**
+ * Decodes every string in a binary string pool, for small pools that
+ * are used often enough to keep in memory.
+ */
+export function getPoolStrings(pool: DataView): string[] {
+  const result = [];
+  for (let i = 0; i < getPoolLength(pool); i++)
+    result.push(getPoolString(pool, i));
+  return result;
 }
 
 /**
@@ -161,7 +305,7 @@ export function getNameProperty(data: Data, point: number): string | null {
     return `${prefix}${pointToYouPlus(point, "")}`;
   }
 
-  return getString0(data, "name", point);
+  return getName(data, point);
 }
 
 /**
@@ -201,7 +345,22 @@ export function getNameExceptNr2(data: Data, point: number): string | null {
  */
 export function getOldName(data: Data, point: number): string | null {
   // FIXME figment/control/correction
-  return getString(data, "uhdef", point) ?? getString0(data, "name", point);
+  return getUnihan(data, "kDefinition", point) ?? getName(data, point);
+}
+
+/**
+ * Returns the value of the given Unihan field (like kMandarin) for the
+ * given point, or null if the generator didn’t keep that field.
+ */
+export function getUnihan(
+  data: Data,
+  key: string,
+  point: number,
+): string | null {
+  const view = data.unihan[key];
+  if (view == null) return null;
+
+  return getStringByIndex(data, getSparseIndex(view, point));
 }
 
 export function getHangulSyllableName(
@@ -213,7 +372,7 @@ export function getHangulSyllableName(
   const V_BASE = 0x1161;
   const T_BASE = 0x11a7;
 
-  const lvt = getSparse(Uint16, data.hlvt, 0, point);
+  const lvt = getSparse(data.hlvt, 0, point);
   const [present, l, v, t] = [
     (lvt >> 15) & 0b1,
     (lvt >> 10) & 0b11111,
@@ -232,23 +391,40 @@ export function getHangulSyllableName(
 }
 
 export function getAliasCount(data: Data, point: number): number {
-  return getSparse(Uint8, data.aliasc, 0, point);
+  return getSparse(data.aliasc, 0, point);
 }
 
 export function getAliasBaseIndex(data: Data, point: number): number | null {
-  return getSparse(Uint16, data.aliasi, null, point);
+  return getSparseIndex(data.aliasi, point);
 }
 
 export function getAliasValue(data: Data, aliasIndex: number): string | null {
-  const ty = Uint16;
-  const offset = aliasIndex * ty.len;
-  return getStringByIndex(data, data.aliass[ty.method](offset));
+  const width = getIndexWidth(data, "data.aliass.bin");
+  const index = getUint(data.aliass, aliasIndex * width, width);
+  return getStringByIndex(data, index);
 }
 
 export function getAliasType(data: Data, aliasIndex: number): AliasType | null {
-  const ty = Uint8;
-  const offset = aliasIndex * ty.len;
-  return data.aliast[ty.method](offset);
+  return data.aliast.getUint8(aliasIndex);
+}
+
+export function getVariantCount(data: Data, point: number): number {
+  return getSparse(data.varc, 0, point);
+}
+
+export function getVariantBaseIndex(
+  data: Data,
+  point: number,
+): number | null {
+  return getSparseIndex(data.vari, point);
+}
+
+export function getVariantPoint(data: Data, variantIndex: number): number {
+  return data.varp.getUint32(variantIndex * 4);
+}
+
+export function getVariantType(data: Data, variantIndex: number): VariantType {
+  return data.vart.getUint8(variantIndex);
 }
 
 export function findSequenceBucket(
@@ -256,19 +432,21 @@ export function findSequenceBucket(
   firstPoint: number,
   secondPoint: number,
 ): SequenceBucket | null {
+  const width = getIndexWidth(data, "data.seqb.bin");
+  const size = 9 + width;
   let h = 0,
     i = 0,
-    j = data.seqb.byteLength / 11;
+    j = data.seqb.byteLength / size;
   while (h < j) {
     i = h + Math.floor((j - h) / 2);
-    const x = data.seqb.getUint32(i * 11 + 0);
+    const x = data.seqb.getUint32(i * size + 0);
     if (x != firstPoint) {
       if (j - h == 1) return null;
       else if (x < firstPoint) h = i;
       else if (x > firstPoint) j = i;
       continue;
     }
-    const y = data.seqb.getUint32(i * 11 + 4);
+    const y = data.seqb.getUint32(i * size + 4);
     if (y != secondPoint) {
       if (j - h == 1) return null;
       else if (y < secondPoint) h = i;
@@ -278,8 +456,8 @@ export function findSequenceBucket(
       break;
     }
   }
-  const start = data.seqb.getUint16(i * 11 + 8);
-  const len = data.seqb.getUint8(i * 11 + 10);
+  const start = getUint(data.seqb, i * size + 8, width);
+  const len = data.seqb.getUint8(i * size + 8 + width);
   return { start, len };
 }
 
@@ -302,25 +480,42 @@ export function getSequencePoints(
   data: Data,
   sequenceIndex: number,
 ): number[] | null {
-  const start = data.seqp.getUint16(sequenceIndex * 3 + 0);
-  const len = data.seqp.getUint8(sequenceIndex * 3 + 2);
-  const base = data.info.sequenceCount * 3;
+  const start = data.seqp.getUint32(sequenceIndex * 5 + 0);
+  const len = data.seqp.getUint8(sequenceIndex * 5 + 4);
+  const base = data.info.sequenceCount * 5;
   const result = [];
   for (let i = start; i < start + len; i++)
     result.push(data.seqp.getUint32(base + i * 4));
   return result;
 }
 
+/**
+ * Returns the kinds of the given sequence, that is, which input files
+ * define it (see data/src/sequence.rs).
+ */
+export function getSequenceKinds(
+  data: Data,
+  sequenceIndex: number,
+): SequenceBits[] {
+  const bits = data.seqk.getUint8(sequenceIndex);
+  return Object.values(SequenceBits).filter(
+    (x): x is SequenceBits => typeof x == "number" && !!((bits >> x) & 1),
+  );
+}
+
 export function getSequenceNames(
   data: Data,
   sequenceIndex: number,
 ): string[] | null {
-  const start = data.seqn.getUint16(sequenceIndex * 3 + 0);
-  const len = data.seqn.getUint8(sequenceIndex * 3 + 2);
-  const base = data.info.sequenceCount * 3;
+  const start = data.seqn.getUint32(sequenceIndex * 5 + 0);
+  const len = data.seqn.getUint8(sequenceIndex * 5 + 4);
+  const base = data.info.sequenceCount * 5;
+  const width = getIndexWidth(data, "data.seqn.bin");
   const result = [];
   for (let i = start; i < start + len; i++)
-    result.push(getStringByIndex(data, data.seqn.getUint16(base + i * 2))!);
+    result.push(
+      getStringByIndex(data, getUint(data.seqn, base + i * width, width))!,
+    );
   return result;
 }
 
@@ -329,78 +524,226 @@ export function getSequenceNameByIndices(
   sequenceIndex: number,
   sequenceNameIndex: number,
 ): string | null {
-  const start = data.seqn.getUint16(sequenceIndex * 3 + 0);
-  const len = data.seqn.getUint16(sequenceIndex * 3 + 2);
+  const start = data.seqn.getUint32(sequenceIndex * 5 + 0);
+  const len = data.seqn.getUint8(sequenceIndex * 5 + 4);
   if (sequenceNameIndex < 0 || sequenceNameIndex >= len) return null;
 
-  const base = data.info.sequenceCount * 3;
+  const base = data.info.sequenceCount * 5;
+  const width = getIndexWidth(data, "data.seqn.bin");
   return getStringByIndex(
     data,
-    data.seqn.getUint16(base + (start + sequenceNameIndex) * 2),
+    getUint(data.seqn, base + (start + sequenceNameIndex) * width, width),
   );
 }
 
+/**
+ * Returns the emoji-test.txt status of the given point, or null if the
+ * point is not listed there.
+ */
+export function getEmojiStatus(data: Data, point: number): EmojiStatus | null {
+  const status = getSparse(data.estatus, 0, point);
+  return status > 0 ? status : null;
+}
+
+/**
+ * Returns the sequence index of the fully-qualified form of the given
+ * point, or null if the point is fully-qualified or not an emoji.
+ */
+export function getEmojiQualifiedIndex(
+  data: Data,
+  point: number,
+): number | null {
+  return getSparseIndex(data.equal, point);
+}
+
+export function getSequenceEmojiTest(
+  data: Data,
+  sequenceIndex: number,
+): SequenceEmojiTest | null {
+  // order (u16), group, subgroup, version, qualified, then status (u8)
+  const width = getIndexWidth(data, "data.seqe.bin");
+  const offset = sequenceIndex * (2 + 4 * width + 1);
+  const status = data.seqe.getUint8(offset + 2 + 4 * width);
+  if (status == 0) return null;
+
+  const index = (i: number) => {
+    const result = getUint(data.seqe, offset + 2 + i * width, width);
+    return isNoIndex(result, width) ? null : result;
+  };
+  return {
+    group: getStringByIndex(data, index(0)),
+    subgroup: getStringByIndex(data, index(1)),
+    version: getStringByIndex(data, index(2)),
+    qualified: index(3),
+    status,
+  };
+}
+
+/**
+ * Returns true if the given name of the given sequence is provisional
+ * (from NamedSequencesProv.txt).
+ */
+export function isSequenceNameProvisional(
+  data: Data,
+  sequenceIndex: number,
+  sequenceNameIndex: number,
+): boolean {
+  // the flags follow the names of every sequence, so skip past them
+  const last = (data.info.sequenceCount - 1) * 5;
+  const total = data.seqn.getUint32(last) + data.seqn.getUint8(last + 4);
+  const start = data.seqn.getUint32(sequenceIndex * 5 + 0);
+  const base = data.info.sequenceCount * 5;
+  const width = getIndexWidth(data, "data.seqn.bin");
+  const offset = base + total * width + start + sequenceNameIndex;
+  return !!(data.seqn.getUint8(offset) & 1);
+}
+
+export function getSequenceAliasCount(
+  data: Data,
+  sequenceIndex: number,
+): number {
+  return data.seqa.getUint8(sequenceIndex * 5 + 4);
+}
+
+export function getSequenceAliasValue(
+  data: Data,
+  sequenceIndex: number,
+  sequenceAliasIndex: number,
+): string | null {
+  const start = data.seqa.getUint32(sequenceIndex * 5 + 0);
+  const len = data.seqa.getUint8(sequenceIndex * 5 + 4);
+  if (sequenceAliasIndex < 0 || sequenceAliasIndex >= len) return null;
+
+  const base = data.info.sequenceCount * 5;
+  const width = getIndexWidth(data, "data.seqa.bin");
+  return getStringByIndex(
+    data,
+    getUint(data.seqa, base + (start + sequenceAliasIndex) * width, width),
+  );
+}
+
+export function getSequenceAliasType(
+  data: Data,
+  sequenceIndex: number,
+  sequenceAliasIndex: number,
+): AliasType | null {
+  const start = data.seqa.getUint32(sequenceIndex * 5 + 0);
+  const len = data.seqa.getUint8(sequenceIndex * 5 + 4);
+  if (sequenceAliasIndex < 0 || sequenceAliasIndex >= len) return null;
+
+  // the types follow the aliases of every sequence, so skip past them
+  const last = (data.info.sequenceCount - 1) * 5;
+  const total = data.seqa.getUint32(last) + data.seqa.getUint8(last + 4);
+  const base = data.info.sequenceCount * 5;
+  const width = getIndexWidth(data, "data.seqa.bin");
+  return data.seqa.getUint8(
+    base + total * width + start + sequenceAliasIndex,
+  );
+}
+
+// Normalises a pinyin query to a key in the kMandarin reading index, where
+// tone marks become a trailing tone number (lán → lan2) and ü is spelled v.
+export function toPinyinKey(query: string): string {
+  const marks = ["\u0304", "\u0301", "\u030C", "\u0300"];
+  let result = "";
+  let tone = 0;
+
+  for (const c of query
+    .toLowerCase()
+    .normalize("NFD")
+    .replace(/u\u0308|u:/g, "v")) {
+    const i = marks.indexOf(c);
+    if (i != -1) tone = Math.max(tone, i + 1);
+    else if (c < "\u0300" || c > "\u036F") result += c;
+  }
+
+  return tone > 0 ? `${result}${tone}` : result;
+}
+
+export function findPinyinPoints(data: Data, query: string): number[] {
+  const key = toPinyinKey(query);
+  let h = 0,
+    j = data.pyk.length;
+  while (h < j) {
+    const i = h + Math.floor((j - h) / 2);
+    if (data.pyk[i] < key) h = i + 1;
+    else j = i;
+  }
+  if (data.pyk[h] != key) return [];
+
+  const start = data.pyi.getUint32(h * 8 + 0);
+  const len = data.pyi.getUint32(h * 8 + 4);
+  const result = [];
+  for (let i = start; i < start + len; i++)
+    result.push(data.pyp.getUint32(i * 4));
+  return result;
+}
+
 export function getGraphemeBreak(
   data: Data,
   point: number,
 ): GraphemeBreak | null {
-  return getSparse(Uint8, data.gb, null, point);
+  return getSparse(data.gb, null, point);
 }
 
 export function kDefinitionExists(data: Data, point: number): boolean {
-  return getFlag(data, 0, point);
+  return getFlag(data, Bits.KdefinitionExists, point);
 }
 
 export function isSpaceSeparator(data: Data, point: number): boolean {
-  return getFlag(data, 2, point);
+  return getFlag(data, Bits.IsSpaceSeparator, point);
 }
 
 export function isAnyMark(data: Data, point: number): boolean {
-  return getFlag(data, 3, point);
+  return getFlag(data, Bits.IsAnyMark, point);
 }
 
 export function hasDerivedNameNr1(data: Data, point: number): boolean {
-  return getFlag(data, 4, point);
+  return getFlag(data, Bits.DerivedNameNr1, point);
 }
 
 export function hasDerivedNameNr2(data: Data, point: number): boolean {
-  return getFlag(data, 5, point);
+  return getFlag(data, Bits.DerivedNameNr2, point);
 }
 
 export function hasAnyNameExceptNr2(data: Data, page: number): boolean {
-  return getPageFlag(data, 0, page);
+  return getPageFlag(data, PageBits.HasAnyNameExceptNr2, page);
 }
 
 export function hasAnyUhdef(data: Data, page: number): boolean {
-  return getPageFlag(data, 1, page);
+  return getPageFlag(data, PageBits.HasAnyUhdef, page);
 }
 
 export function hasAnyAlias(data: Data, page: number): boolean {
-  return getPageFlag(data, 2, page);
+  return getPageFlag(data, PageBits.HasAnyAlias, page);
+}
+
+export function hasAnyUnihanReading(data: Data, page: number): boolean {
+  return getPageFlag(data, PageBits.HasAnyUnihanReading, page);
 }
 
 export function isEmoji(data: Data, point: number): boolean {
-  return getEmojiFlag(data, 0, point);
+  return getEmojiFlag(data, EmojiBits.Emoji, point);
 }
 
 export function isExtendedPictographic(data: Data, point: number): boolean {
-  return getEmojiFlag(data, 1, point);
+  return getEmojiFlag(data, EmojiBits.ExtendedPictographic, point);
 }
 
 export function isEmojiComponent(data: Data, point: number): boolean {
-  return getEmojiFlag(data, 2, point);
+  return getEmojiFlag(data, EmojiBits.EmojiComponent, point);
 }
 
 export function isEmojiPresentation(data: Data, point: number): boolean {
-  return getEmojiFlag(data, 3, point);
+  return getEmojiFlag(data, EmojiBits.EmojiPresentation, point);
 }
 
 export function isEmojiModifier(data: Data, point: number): boolean {
-  return getEmojiFlag(data, 4, point);
+  return getEmojiFlag(data, EmojiBits.EmojiModifier, point);
 }
 
 export function isEmojiModifierBase(data: Data, point: number): boolean {
-  return getEmojiFlag(data, 5, point);
+  return getEmojiFlag(data, EmojiBits.EmojiModifierBase, point);
 }
 
 interface ClusterBreaker {
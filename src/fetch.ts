@@ -1,111 +1,191 @@
 import info from "../data/data.info.json";
-import string from "../data/data.string.bin";
-import bits from "../data/data.bits.bin";
-import ebits from "../data/data.ebits.bin";
-import pagebits from "../data/data.pagebits.bin";
-import name from "../data/data.name.bin";
-import aliasc from "../data/data.aliasc.bin";
-import aliasi from "../data/data.aliasi.bin";
-import aliass from "../data/data.aliass.bin";
-import aliast from "../data/data.aliast.bin";
-import dnrp from "../data/data.dnrp.bin";
-import gb from "../data/data.gb.bin";
-import gc from "../data/data.gc.bin";
-import block from "../data/data.block.bin";
-import age from "../data/data.age.bin";
-import hlvt from "../data/data.hlvt.bin";
-import hjsn from "../data/data.hjsn.bin";
-import uhdef from "../data/data.uhdef.bin";
-import uhman from "../data/data.uhman.bin";
-import seqb from "../data/data.seqb.bin";
-import seqp from "../data/data.seqp.bin";
-import seqn from "../data/data.seqn.bin";
-
-import { Data } from "./data";
-
-export function fetchAllData(): Promise<Data> {
-  return fetchData(
-    string,
-    bits,
-    ebits,
-    pagebits,
-    name,
-    aliasc,
-    aliasi,
-    aliass,
-    aliast,
-    dnrp,
-    gb,
-    gc,
-    block,
-    age,
-    hlvt,
-    hjsn,
-    uhdef,
-    uhman,
-    seqb,
-    seqp,
-    seqn,
-  );
+
+import { DataFile, FILES, SectionType } from "../data/schema";
+
+import { Chunked, Data, getPoolStrings } from "./data";
+
+type DataField = Exclude<keyof Data, "info" | "unihan">;
+
+// Each field in Data is read from data.<field>.bin, so a field that the
+// generator no longer writes (see data/schema.ts) is a type error here
+// rather than a 404 at runtime.
+const FIELDS: {
+  [K in DataField]: `data.${K}.bin` extends DataFile ? K : never;
+}[DataField][] = [
+  "string",
+  "bits",
+  "ebits",
+  "pagebits",
+  "name",
+  "nametok",
+  "nameseq",
+  "aliasc",
+  "aliasi",
+  "aliass",
+  "aliast",
+  "varc",
+  "vari",
+  "varp",
+  "vart",
+  "dnrp",
+  "gb",
+  "gc",
+  "block",
+  "age",
+  "hlvt",
+  "hjsn",
+  "egroup",
+  "esubgroup",
+  "eversion",
+  "estatus",
+  "equal",
+  "seqb",
+  "seqp",
+  "seqn",
+  "seqa",
+  "seqe",
+  "seqk",
+  "pyk",
+  "pyi",
+  "pyp",
+];
+
+// URL of each file that the generator wrote, including the chunks of
+// each sparse file when it splits them ($CHUNK_PAGES in data/src/main.rs).
+const PATHS = require.context("../data", false, /^[.][/]data[.].+[.]bin$/);
+
+// The chunks of each sparse file and the points they cover, if the
+// generator split them (see write_manifest in data/src/write.rs).
+interface Manifest {
+  chunkPages: number;
+  files: { [path: string]: string };
+  chunks: ManifestChunk[];
+}
+
+interface ManifestChunk {
+  first: number;
+  last: number;
+  files: { [file: string]: { path: string; hash: string } };
 }
 
-async function fetchData(
-  stringPath: string,
-  ...paths: string[]
-): Promise<Data> {
-  const [
-    string,
-    [
-      bits,
-      ebits,
-      pagebits,
-      name,
-      aliasc,
-      aliasi,
-      aliass,
-      aliast,
-      dnrp,
-      gb,
-      gc,
-      block,
-      age,
-      hlvt,
-      hjsn,
-      uhdef,
-      uhman,
-      seqb,
-      seqp,
-      seqn,
-    ],
-  ] = await Promise.all([
-    fetchJson<string[]>(stringPath),
-    Promise.all(paths.map(fetchDataView)),
+const MANIFESTS = require.context(
+  "../data",
+  false,
+  /^[.][/]data[.]manifest[.]json$/,
+);
+const manifest: Manifest | null =
+  MANIFESTS.keys().length > 0 ? MANIFESTS("./data.manifest.json") : null;
+
+// Fetches of each chunk by path, so that each chunk is fetched only once.
+const chunkFetches = new Map<string, Promise<DataView>>();
+
+/**
+ * Fetches the data files, plus the chunks for Plane 0 if the generator
+ * split the sparse files into chunks. Other chunks are fetched by
+ * fetchChunksFor and fetchAllChunks, when first used.
+ */
+export async function fetchInitialData(): Promise<Data> {
+  const result: { [field: string]: any } = { info, unihan: {} };
+  await Promise.all([
+    ...FIELDS.map(async (field) => {
+      result[field] = await fetchFile(`data.${field}.bin` as DataFile);
+    }),
+    ...info.unihanFields.map(async ({ key, file }) => {
+      result.unihan[key] = await fetchFile(`data.${file}.bin` as DataFile);
+    }),
   ]);
+  result.nametok = getPoolStrings(result.nametok);
+
+  const data = result as Data;
+  return (await fetchChunks(data, (x) => x.first < 0x10000)) ?? data;
+}
+
+/**
+ * Fetches the data files, including every chunk.
+ */
+export async function fetchAllData(): Promise<Data> {
+  return fetchAllChunks(await fetchInitialData());
+}
+
+/**
+ * Fetches every chunk not yet fetched, returning a new Data.
+ */
+export async function fetchAllChunks(data: Data): Promise<Data> {
+  return (await fetchChunks(data, () => true)) ?? data;
+}
+
+/**
+ * Fetches the chunks for the given ranges of points (first and last), if
+ * not yet fetched. Returns a new Data if any were fetched, otherwise null.
+ */
+export async function fetchChunksFor(
+  data: Data,
+  ranges: [number, number][],
+): Promise<Data | null> {
+  return fetchChunks(data, (x) =>
+    ranges.some(([first, last]) => x.first <= last && first <= x.last),
+  );
+}
+
+async function fetchChunks(
+  data: Data,
+  predicate: (chunk: ManifestChunk) => boolean,
+): Promise<Data | null> {
+  if (manifest == null) return null;
+
+  const fields = [
+    ...FIELDS.map((x): [unknown, string] => [data[x], `data.${x}.bin`]),
+    ...info.unihanFields.map(({ key, file }): [unknown, string] => [
+      data.unihan[key],
+      `data.${file}.bin`,
+    ]),
+  ].filter((x): x is [Chunked, string] => isChunked(x[0]));
+  const fetches: Promise<void>[] = [];
+
+  manifest.chunks.forEach((chunk, i) => {
+    if (!predicate(chunk)) return;
+
+    for (const [field, file] of fields) {
+      if (!(file in chunk.files) || field.chunks[i] !== undefined) continue;
+
+      const path = chunk.files[file].path;
+      if (!chunkFetches.has(path))
+        chunkFetches.set(path, fetchDataView(PATHS(`./${path}`)));
+      fetches.push(
+        chunkFetches.get(path)!.then((x) => void (field.chunks[i] = x)),
+      );
+    }
+  });
+
+  if (fetches.length == 0) return null;
+  await Promise.all(fetches);
+
+  // chunks are fetched into the existing fields, but clients need a new
+  // object to know that something changed
+  return { ...data };
+}
+
+function isChunked(field: unknown): field is Chunked {
+  return field instanceof Object && "chunks" in field;
+}
+
+// Fetches a file, parsed according to the type of its elements. Sparse
+// files that were split into chunks start with none of them fetched.
+async function fetchFile(file: DataFile): Promise<unknown> {
+  if (manifest != null && !(file in manifest.files)) {
+    const chunked: Chunked = {
+      chunkPoints: manifest.chunkPages * 0x100,
+      chunks: manifest.chunks.map((x) =>
+        file in x.files ? undefined : null,
+      ),
+    };
+    return chunked;
+  }
 
-  return {
-    info,
-    string,
-    bits,
-    ebits,
-    pagebits,
-    name,
-    aliasc,
-    aliasi,
-    aliass,
-    aliast,
-    dnrp,
-    gb,
-    gc,
-    block,
-    age,
-    hlvt,
-    hjsn,
-    uhdef,
-    uhman,
-    seqb,
-    seqp,
-    seqn,
-  };
+  const path = PATHS(`./${file}`);
+  return FILES[file] == SectionType.Json
+    ? fetchJson<unknown>(path)
+    : fetchDataView(path);
 }
 
 async function fetchJson<T>(path: string): Promise<T> {
@@ -4,6 +4,7 @@ import "./new.sass";
 
 import React, {
   CSSProperties,
+  Fragment,
   Dispatch,
   SetStateAction,
   useState,
@@ -11,6 +12,7 @@ import React, {
   useContext,
   useRef,
   useMemo,
+  useCallback,
   StrictMode,
 } from "react";
 import ReactDOMClient from "react-dom/client";
@@ -36,17 +38,35 @@ import {
 import {
   AliasType,
   Data,
+  EmojiStatus,
   findSequenceIndex,
   getAliasBaseIndex,
   getAliasCount,
   getAliasType,
   getAliasValue,
+  getEmojiQualifiedIndex,
+  getEmojiStatus,
   getNameProperty,
   getNextClusterBreak,
+  getSequenceAliasCount,
+  getSequenceAliasType,
+  getSequenceAliasValue,
+  getSequenceEmojiTest,
+  getSequenceKinds,
   getSequenceNameByIndices,
+  getSequencePoints,
   getString,
+  getUnihan,
+  isSequenceNameProvisional,
+  getVariantBaseIndex,
+  getVariantCount,
+  getVariantPoint,
+  getVariantType,
   isEmoji,
   isEmojiPresentation,
+  SequenceBits,
+  StringField,
+  VariantType,
 } from "./data";
 import { pointsToString, stringToPoints } from "./encoding";
 import {
@@ -61,7 +81,7 @@ import {
 import { Display } from "./Display";
 import { KeyedSearchResult, search, SearchResult } from "./search";
 import { nullToDefault } from "./default";
-import { fetchAllData } from "./fetch";
+import { fetchAllChunks, fetchChunksFor, fetchInitialData } from "./fetch";
 
 const {
   clientWidth: mapContentWidth,
@@ -86,9 +106,29 @@ function Charming() {
   const location = useLocation();
   const points = getHashPoints(location.hash, [0]);
 
-  useEffect(() => void fetchAllData().then(setData), []);
+  // Plane 0 first, then the chunks for other points when first used
+  const fetchChunks = useCallback(
+    (ranges: [number, number][]) => {
+      if (data == null) return;
+      fetchChunksFor(data, ranges).then((x) => void (x && setData(x)));
+    },
+    [data],
+  );
+
+  useEffect(() => void fetchInitialData().then(setData), []);
   useEffect(() => void fixHashPoints(location.hash!, points));
 
+  useEffect(
+    () => fetchChunks(points.map((x): [number, number] => [x, x])),
+    [fetchChunks, location.hash],
+  );
+
+  // Search looks at every point, so it needs every chunk
+  useEffect(() => {
+    if (data != null && searchEverOpened)
+      fetchAllChunks(data).then((x) => void (x != data && setData(x)));
+  }, [data, searchEverOpened]);
+
   useEffect(() => {
     if (data != null) {
       document.title = ifSequence(
@@ -179,7 +219,7 @@ function Charming() {
       <DataContext.Provider value={data}>
         <PointsContext.Provider value={points}>
           <Detail search={openSearch} />
-          <Map />
+          <Map fetchChunks={fetchChunks} />
           {searchEverOpened && (
             <Search
               query={searchQuery}
@@ -283,8 +323,17 @@ function PointDetails({ point }: { point: number }) {
         <StringPair field="block" label="Block" />
         <StringPair field="age" label="Introduced in" />
         <StringPair field="hjsn" label="Hangul Jamo short name" />
-        <StringPair field="uhdef" label="Unihan kDefinition" />
-        <StringPair field="uhman" label="Unihan kMandarin" />
+        {data.info.unihanFields.map(({ key }) => (
+          <UnihanPair key={key} field={key} />
+        ))}
+        <VariantPairs point={point} />
+        <StringPair field="egroup" label="Emoji group" />
+        <StringPair field="esubgroup" label="Emoji subgroup" />
+        <StringPair field="eversion" label="Emoji version" />
+        <EmojiTestPairs
+          status={getEmojiStatus(data, point)}
+          qualified={getEmojiQualifiedIndex(data, point)}
+        />
         {emoji && <dt>Emoji properties</dt>}
         {emoji && (
           <dd>
@@ -299,6 +348,11 @@ function PointDetails({ point }: { point: number }) {
 
 function SequenceDetails({ points }: { points: number[] }) {
   const data = useContext(DataContext)!;
+  const sequenceIndex = findSequenceIndex(data, points);
+  const etest =
+    sequenceIndex != null ? getSequenceEmojiTest(data, sequenceIndex) : null;
+  const kinds =
+    sequenceIndex != null ? getSequenceKinds(data, sequenceIndex) : [];
 
   return (
     <>
@@ -308,6 +362,9 @@ function SequenceDetails({ points }: { points: number[] }) {
           count={getAliasCount(data, points[0])}
         />
       )}
+      {sequenceIndex != null && (
+        <SequenceAliasList sequenceIndex={sequenceIndex} />
+      )}
       <ul>
         {points.map((x, i) => (
           <li key={i}>
@@ -336,6 +393,22 @@ function SequenceDetails({ points }: { points: number[] }) {
           value={joinSequence(points, " ", (x) => pointToEntity10(x))}
           label="HTML"
         />
+        {kinds.length > 0 && (
+          <>
+            <dt>Sequence kinds</dt>
+            {kinds.map((x) => (
+              <dd key={x}>{SEQUENCE_KINDS[x][1]}</dd>
+            ))}
+          </>
+        )}
+        {etest && (
+          <>
+            <Pair value={etest.group} label="Emoji group" />
+            <Pair value={etest.subgroup} label="Emoji subgroup" />
+            <Pair value={etest.version} label="Emoji version" />
+            <EmojiTestPairs status={etest.status} qualified={etest.qualified} />
+          </>
+        )}
       </dl>
     </>
   );
@@ -454,7 +527,7 @@ const SearchResultRow = React.memo(function SearchResultRow({
   const classes = (useSequenceStyle ? ["sequence"] : []).join(" ");
 
   const hint =
-    x.reason == "alias" ? (
+    x.reason == "alias" || x.reason == "sequenceAlias" ? (
       <>
         {space}
         <span>
@@ -462,6 +535,14 @@ const SearchResultRow = React.memo(function SearchResultRow({
         </span>
         {space}
       </>
+    ) : x.reason == "sequenceName" || x.reason == "sequenceValue" ? (
+      <>
+        {space}
+        <span>
+          <SequenceKindHint sequenceIndex={x.sequenceIndex} />
+        </span>
+        {space}
+      </>
     ) : (
       <>{space}</>
     );
@@ -564,6 +645,29 @@ function SearchResultLabel({
             query={query}
             offset={result.offset}
           />
+          {isSequenceNameProvisional(
+            data,
+            result.sequenceIndex,
+            result.sequenceNameIndex,
+          ) && <> (provisional)</>}
+        </>
+      );
+    case "sequenceAlias":
+      return (
+        <>
+          {pointsToYouPlusEllipsis(points)}
+          {separator}
+          <SubstringMatches
+            label={
+              getSequenceAliasValue(
+                data,
+                result.sequenceIndex,
+                result.sequenceAliasIndex,
+              )!
+            }
+            query={query}
+            offset={result.offset}
+          />
         </>
       );
     case "name":
@@ -584,7 +688,19 @@ function SearchResultLabel({
           {pointToYouPlus(point)}
           {separator}
           <SubstringMatches
-            label={getString(data, reason, point)!}
+            label={getUnihan(data, "kDefinition", point)!}
+            query={query}
+            offset={result.offset}
+          />
+        </>
+      );
+    case "uhreading":
+      return (
+        <>
+          {pointToYouPlus(point)}
+          {separator}
+          <SubstringMatches
+            label={getUnihan(data, result.field, point)!}
             query={query}
             offset={result.offset}
           />
@@ -616,17 +732,55 @@ function AliasHint({ type }: { type: AliasType }) {
     "abbreviation: commonly occurring abbreviations (acronyms) for control codes, format characters, spaces, and variation selectors",
     "Unicode_1_Name: old names of characters prior to the prohibition of character name changes in Unicode 2.0",
     "CLDR short name: a name that may change over time, and for emoji, might reflect the preferred depiction more accurately",
+    "CLDR keyword: a word or phrase commonly used to search for an emoji",
+    "informal: other names from the Unicode code charts, which are not normative",
   ][type];
 
   return (
     <small className="AliasHint">
       <abbr title={title}>
-        {["corr", "ctrl", "alt", "fig", "abbr", "u1", "cldr"][type]}
+        {
+          ["corr", "ctrl", "alt", "fig", "abbr", "u1", "cldr", "kw", "inf"][
+            type
+          ]
+        }
       </abbr>
     </small>
   );
 }
 
+// abbreviation and description of each kind of sequence, by bit
+const SEQUENCE_KINDS: { [K in SequenceBits]: [string, string] } = {
+  [SequenceBits.NamedSequence]: ["ns", "named sequence"],
+  [SequenceBits.EmojiTest]: ["emoji", "emoji in emoji-test.txt"],
+  [SequenceBits.StandardizedVariant]: ["svs", "standardized variant"],
+  [SequenceBits.EmojiVariation]: ["evs", "emoji variation sequence"],
+  [SequenceBits.Ivd]: ["ivs", "ideographic variation sequence"],
+  [SequenceBits.NamedSequenceProvisional]: [
+    "prov",
+    "provisional named sequence",
+  ],
+  [SequenceBits.DerivedFlag]: ["flag", "flag with a derived name"],
+};
+
+function SequenceKindHint({ sequenceIndex }: { sequenceIndex: number }) {
+  const data = useContext(DataContext);
+  const space = " ";
+
+  if (data == null) return null;
+
+  return (
+    <small className="AliasHint">
+      {getSequenceKinds(data, sequenceIndex).map((x, i) => (
+        <Fragment key={x}>
+          {i > 0 && space}
+          <abbr title={SEQUENCE_KINDS[x][1]}>{SEQUENCE_KINDS[x][0]}</abbr>
+        </Fragment>
+      ))}
+    </small>
+  );
+}
+
 function SubstringMatches({
   label,
   query,
@@ -685,11 +839,97 @@ function AliasPair({ index }: { index: number }) {
   );
 }
 
+function SequenceAliasList({ sequenceIndex }: { sequenceIndex: number }) {
+  const data = useContext(DataContext)!;
+  const count = getSequenceAliasCount(data, sequenceIndex);
+  const space = " ";
+  const result = [];
+
+  if (count == 0) return null;
+
+  for (let i = 0; i < count; i++)
+    result.push(
+      <li key={i}>
+        <span className="marker">
+          <AliasHint type={getSequenceAliasType(data, sequenceIndex, i)!} />
+          {space}
+        </span>
+        {getSequenceAliasValue(data, sequenceIndex, i)!}
+      </li>,
+    );
+
+  return <ul className="AliasList">{result}</ul>;
+}
+
+function VariantPairs({ point }: { point: number }) {
+  const data = useContext(DataContext)!;
+  const start = getVariantBaseIndex(data, point);
+  const count = getVariantCount(data, point);
+  const result = [];
+
+  if (start == null) return null;
+
+  for (let i = start; i < start + count; i++) {
+    const type = getVariantType(data, i);
+    const x = getVariantPoint(data, i);
+    result.push(
+      <Fragment key={i}>
+        <dt>Unihan k{VariantType[type]}Variant</dt>
+        <dd>
+          <a href={toFragment([x])}>
+            <Display points={[x]} /> {pointToYouPlus(x)}
+          </a>
+        </dd>
+      </Fragment>,
+    );
+  }
+
+  return <>{result}</>;
+}
+
+function EmojiTestPairs({
+  status,
+  qualified,
+}: {
+  status: EmojiStatus | null;
+  qualified: number | null;
+}) {
+  const data = useContext(DataContext)!;
+
+  if (status == null) return null;
+
+  // status field in emoji-test.txt
+  const label = [
+    null,
+    "component",
+    "fully-qualified",
+    "minimally-qualified",
+    "unqualified",
+  ][status];
+  const points = qualified != null ? getSequencePoints(data, qualified) : null;
+
+  return (
+    <>
+      <Pair label="Emoji status" value={label} />
+      {points && (
+        <>
+          <dt>Fully-qualified form</dt>
+          <dd>
+            <a href={toFragment(points)}>
+              <Display points={points} /> {pointsToYouPlus(points)}
+            </a>
+          </dd>
+        </>
+      )}
+    </>
+  );
+}
+
 function StringPair({
   label,
   field,
 }: {
-  field: "gc" | "block" | "age" | "hjsn" | "uhdef" | "uhman";
+  field: StringField;
   label: string;
 }) {
   const data = useContext(DataContext);
@@ -703,6 +943,20 @@ function StringPair({
   return <Pair label={label} value={getString(data, field, point)} />;
 }
 
+function UnihanPair({ field }: { field: string }) {
+  const data = useContext(DataContext);
+  const points = useContext(PointsContext);
+
+  if (data == null || points.length > 1) {
+    return null;
+  }
+
+  const point = points[0];
+  return (
+    <Pair label={`Unihan ${field}`} value={getUnihan(data, field, point)} />
+  );
+}
+
 function Pair({ label, value }: { label: string; value: string | null }) {
   if (value == null) {
     return null;
@@ -716,7 +970,11 @@ function Pair({ label, value }: { label: string; value: string | null }) {
   );
 }
 
-function Map() {
+function Map({
+  fetchChunks,
+}: {
+  fetchChunks: (ranges: [number, number][]) => void;
+}) {
   const points = useContext(PointsContext);
   const point = points.length == 1 ? points[0] : null;
 
@@ -724,7 +982,12 @@ function Map() {
     <div className="Map">
       <AutoSizer defaultWidth={mapWidth} defaultHeight={mapHeight}>
         {({ width, height }: { width: number; height: number }) => (
-          <MapGrid width={width} height={height} point={point} />
+          <MapGrid
+            width={width}
+            height={height}
+            point={point}
+            fetchChunks={fetchChunks}
+          />
         )}
       </AutoSizer>
     </div>
@@ -735,10 +998,12 @@ function MapGrid({
   width,
   height,
   point,
+  fetchChunks,
 }: {
   width: number;
   height: number;
   point: number | null;
+  fetchChunks: (ranges: [number, number][]) => void;
 }) {
   const pointForScroll = point ?? 0;
   const columnCount = Math.floor((width - scrollbar) / 40);
@@ -772,6 +1037,14 @@ function MapGrid({
       overscanRowsCount={16}
       itemData={itemData}
       initialScrollTop={40 * rowIndex}
+      onItemsRendered={({ overscanRowStartIndex, overscanRowStopIndex }) =>
+        fetchChunks([
+          [
+            overscanRowStartIndex * columnCount,
+            (overscanRowStopIndex + 1) * columnCount - 1,
+          ],
+        ])
+      }
     >
       {GridCell}
     </FixedSizeGrid>
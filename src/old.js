@@ -1,7 +1,7 @@
 import "core-js/stable";
 import "regenerator-runtime/runtime";
 
-import { getString, kDefinitionExists, isEmojiPresentation, isSpaceSeparator, getOldName } from "./data";
+import { getString, getUnihan, kDefinitionExists, isEmojiPresentation, isSpaceSeparator, getOldName } from "./data";
 import { fetchAllData } from "./fetch";
 import { pointToString, stringToPoint } from "./encoding";
 import { toHexadecimal, pointToYouPlus, pointToString16, pointToString8, pointToEntity10 } from "./formatting";
@@ -169,7 +169,7 @@ function load_data() {
 }
 
 function get_data(cp, prop) {
-	if (!data || !(prop in data)) {
+	if (!data || !(prop in data) && prop != "uhman") {
 		var substitute = data_defaults[prop];
 		if (typeof substitute == "function")
 			return substitute(cp);
@@ -184,6 +184,8 @@ function get_data(cp, prop) {
 	) {
 		var result = prop == "name"
 			? getOldName(data, cp)
+			: prop == "uhman"
+			? getUnihan(data, "kMandarin", cp)
 			: getString(data, prop, cp);
 		if (result != null) {
 			return result;
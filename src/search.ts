@@ -5,8 +5,10 @@ export type SearchResult = BaseSearchResult &
   (
     | SequenceValueSearchResult
     | SequenceNameSearchResult
+    | SequenceAliasSearchResult
     | NameishSearchResult
     | AliasSearchResult
+    | UnihanReadingSearchResult
     | OtherSearchResult
   );
 
@@ -27,6 +29,14 @@ interface SequenceNameSearchResult {
   sequenceNameIndex: number;
 }
 
+interface SequenceAliasSearchResult {
+  reason: "sequenceAlias";
+  offset: number;
+  sequenceIndex: number;
+  sequenceAliasIndex: number;
+  aliasType: AliasType;
+}
+
 interface NameishSearchResult {
   reason: "name" | "uhdef";
   offset: number;
@@ -39,6 +49,13 @@ interface AliasSearchResult {
   aliasType: AliasType;
 }
 
+interface UnihanReadingSearchResult {
+  reason: "uhreading";
+  offset: number | null;
+  // Unihan key, like kMandarin
+  field: string;
+}
+
 interface OtherSearchResult {
   reason: "hex" | "dec" | "breakdown";
 }
@@ -0,0 +1,36 @@
+import { AliasType } from "./data";
+import { getData, withSequences } from "./testing";
+
+// the worker listens for messages when loaded, so give it somewhere to listen
+(globalThis as any).addEventListener = () => {};
+const { searchBySequenceAlias } =
+  require("./search.worker") as typeof import("./search.worker");
+
+// strings 0, 9 and 10 are "a", "x" and "y" (see getData)
+const data = withSequences(getData(), [
+  { points: [0x0023, 0xfe0f, 0x20e3], aliases: [[0, AliasType.Cldr]] },
+  {
+    points: [0x263a, 0xfe0f],
+    aliases: [
+      [9, AliasType.Cldr],
+      [10, AliasType.CldrKeyword],
+    ],
+  },
+]);
+
+test("searchBySequenceAlias finds sequence by keyword", () =>
+  void expect([...searchBySequenceAlias(data, "Y")]).toEqual([
+    {
+      key: "sequenceName/9786+65039",
+      points: [0x263a, 0xfe0f],
+      reason: "sequenceAlias",
+      sequenceIndex: 1,
+      sequenceAliasIndex: 1,
+      aliasType: AliasType.CldrKeyword,
+      score: 11,
+      offset: 0,
+    },
+  ]));
+
+test("searchBySequenceAlias returns nothing for empty query", () =>
+  void expect([...searchBySequenceAlias(data, "")]).toEqual([]));
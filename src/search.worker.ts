@@ -3,18 +3,23 @@ import "regenerator-runtime/runtime";
 
 import {
   Data,
+  findPinyinPoints,
   findSequenceIndex,
   getAliasCount,
   getAliasType,
   getAliasValue,
   getNameExceptNr2,
   getNextClusterBreak,
+  getSequenceAliasCount,
+  getSequenceAliasType,
+  getSequenceAliasValue,
   getSequenceNames,
   getSequencePoints,
-  getString,
+  getUnihan,
   hasAnyAlias,
   hasAnyNameExceptNr2,
   hasAnyUhdef,
+  hasAnyUnihanReading,
 } from "./data";
 import { pointToString, stringToPoint, stringToPoints } from "./encoding";
 import { toHexadecimal, toDecimal } from "./formatting";
@@ -145,6 +150,38 @@ function* searchBySequenceName(
   }
 }
 
+export function* searchBySequenceAlias(
+  data: Data,
+  query: string,
+): Generator<KeyedSearchResult> {
+  if (query.length == 0) return;
+  const upper = query.toUpperCase();
+
+  for (let i = 0; i < data.info.sequenceCount; i++) {
+    const aliasCount = getSequenceAliasCount(data, i);
+    for (let j = 0; j < aliasCount; j++) {
+      const name = getSequenceAliasValue(data, i, j)!;
+      const type = getSequenceAliasType(data, i, j)!;
+
+      const search = name.toUpperCase();
+      if (search.includes(upper)) {
+        const points = getSequencePoints(data, i)!;
+        const [score, offset] = scoreMatch(search, upper);
+        yield {
+          key: `sequenceName/${points.join("+")}`,
+          points,
+          reason: "sequenceAlias",
+          sequenceIndex: i,
+          sequenceAliasIndex: j,
+          aliasType: type,
+          score,
+          offset,
+        };
+      }
+    }
+  }
+}
+
 function* searchByName(
   data: Data,
   query: string,
@@ -235,7 +272,7 @@ function* searchByUhdef(
     if (!hasAnyUhdef(data, page)) continue;
 
     for (let point = page * 0x100; point < (page + 1) * 0x100; point++) {
-      const uhdef = getString(data, "uhdef", point);
+      const uhdef = getUnihan(data, "kDefinition", point);
       if (uhdef == null) continue;
 
       const search = uhdef.toUpperCase();
@@ -256,6 +293,65 @@ function* searchByUhdef(
     performance.measure(`sBU ${i}`, `sBU ${i} <`, `sBU ${i} >`);
 }
 
+function* searchByUnihanReading(
+  data: Data,
+  query: string,
+): Generator<KeyedSearchResult> {
+  const upper = query.toUpperCase();
+  const fields = data.info.unihanFields
+    .map(({ key }) => key)
+    .filter((x) => x != "kDefinition");
+
+  for (let page = 0; page < 0x1100; page++) {
+    if (page % 0x100 == 0)
+      performance.mark(`sBUR ${Math.floor(page / 0x100)} <`);
+    if (page % 0x100 == 0xff)
+      performance.mark(`sBUR ${Math.floor(page / 0x100)} >`);
+    if (!hasAnyUnihanReading(data, page)) continue;
+
+    for (let point = page * 0x100; point < (page + 1) * 0x100; point++) {
+      for (const field of fields) {
+        const reading = getUnihan(data, field, point);
+        if (reading == null) continue;
+
+        const search = reading.toUpperCase();
+        if (search.includes(upper)) {
+          const [score, offset] = scoreMatch(search, upper);
+          yield {
+            key: `uhreading/${point}`,
+            points: [point],
+            reason: "uhreading",
+            field,
+            score,
+            offset,
+          };
+        }
+      }
+    }
+  }
+
+  for (let i = 0; i < 17; i++)
+    performance.measure(`sBUR ${i}`, `sBUR ${i} <`, `sBUR ${i} >`);
+}
+
+function* searchByPinyin(
+  data: Data,
+  query: string,
+): Generator<KeyedSearchResult> {
+  if (query.length == 0) return;
+
+  for (const point of findPinyinPoints(data, query)) {
+    yield {
+      key: `uhreading/${point}`,
+      points: [point],
+      reason: "uhreading",
+      field: "kMandarin",
+      score: 8,
+      offset: null,
+    };
+  }
+}
+
 function scoreMatch(haystack: string, needle: string): [number, number] {
   let resultScore = 0;
   let resultOffset = haystack.indexOf(needle);
@@ -321,9 +417,16 @@ addEventListener("message", ({ data: { data = cache, query } }) => {
         ...searchByName(data, query),
         ...searchByNameAlias(data, query),
         ...searchBySequenceName(data, query),
+        ...searchBySequenceAlias(data, query),
       ]),
     ),
     ...sortByScore([...searchByUhdef(data, query)]),
+    ...sortByScore(
+      dedupResults([
+        ...searchByPinyin(data, query),
+        ...searchByUnihanReading(data, query),
+      ]),
+    ),
   ];
 
   cache = data;
@@ -1,14 +1,17 @@
-import { Data } from "./data";
+import { AliasType, Data } from "./data";
 
 export function getData(): Data {
   //                  0123456789abc
   const info = {
     sequenceBucketCount: 0,
     sequenceCount: 0,
+    radicalStrokeGroupCount: 0,
+    indexWidths: {},
+    unihanFields: [{ key: "kDefinition", file: "uhdef" }],
   };
-  const string = [..."abcdefghixyz"];
-  const empty = makeSparseWithDonkeyVote(0, () => {});
-  const bits = makeSparseWithDonkeyVote(1 * 3, (result, start) => {
+  const string = makeStringPool([..."abcdefghixyz"]);
+  const empty = makeSparseWithDonkeyVote(1, 0, () => {});
+  const bits = makeSparseWithDonkeyVote(1, 1 * 3, (result, start) => {
     result.setUint8(start + 0, 0b10101010);
     result.setUint8(start + 1, 0b01010101);
     result.setUint8(start + 2, 0b11001111);
@@ -16,59 +19,85 @@ export function getData(): Data {
   // FIXME write tests for ebits, pagebits, alias[cist], gb, seq[bpn]
   const ebits = new DataView(new ArrayBuffer(0x1100));
   const pagebits = new DataView(new ArrayBuffer(0x1100));
-  const name = makeSparseWithDonkeyVote(2 * 3, (result, start) => {
-    result.setUint16(start + 2 * 0, 1); // b
+  const nametok = ["b", "c", "g"];
+  const name = makeSparseWithDonkeyVote(2, 2 * 3, (result, start) => {
+    result.setUint16(start + 2 * 0, 0); // b
     result.setUint16(start + 2 * 1, 2); // c
-    result.setUint16(start + 2 * 2, 6); // g
+    result.setUint16(start + 2 * 2, 4); // g
   });
+  const nameseq = new DataView(new Uint8Array([1, 0, 1, 1, 1, 2]).buffer);
   const aliasc = empty;
   const aliasi = empty;
   const aliass = empty;
   const aliast = empty;
-  const dnrp = makeSparseWithDonkeyVote(2 * 2, (result, start) => {
+  const varc = empty;
+  const vari = empty;
+  const varp = empty;
+  const vart = empty;
+  const dnrp = makeSparseWithDonkeyVote(2, 2 * 2, (result, start) => {
     result.setUint16(start + 2 * 0, 8); // i
     result.setUint16(start + 2 * 1, 7); // h
   });
   const gb = empty;
-  const gc = makeSparseWithDonkeyVote(2 * 2, (result, start) => {
+  const gc = makeSparseWithDonkeyVote(2, 2 * 2, (result, start) => {
     result.setUint16(start + 2 * 0, 3); // d
     result.setUint16(start + 2 * 1, 4); // e
   });
-  const block = makeSparseWithDonkeyVote(2 * 2, (result, start) => {
+  const block = makeSparseWithDonkeyVote(2, 2 * 2, (result, start) => {
     result.setUint16(start + 2 * 0, 12); // (out of bounds)
     result.setUint16(start + 2 * 1, 0xffff); // (null)
   });
   const age = empty;
-  const hlvt = makeSparseWithDonkeyVote(2 * 2, (result, start) => {
+  const hlvt = makeSparseWithDonkeyVote(2, 2 * 2, (result, start) => {
     result.setUint16(start + 2 * 1, 0b1_00000_00000_00001);
   });
-  const hjsn = makeSparseWithDonkeyVote(2 * (0x11a7 + 2), (result, start) => {
-    for (let i = 0; i < 0x11a7 + 2; i++)
-      result.setUint16(start + 2 * i, 0xffff);
-    result.setUint16(start + 2 * (0x1100 + 0), 0x9);
-    result.setUint16(start + 2 * (0x1161 + 0), 0xa);
-    result.setUint16(start + 2 * (0x11a7 + 1), 0xb);
-  });
-  const uhdef = makeSparseWithDonkeyVote(2 * 3, (result, start) => {
+  const hjsn = makeSparseWithDonkeyVote(
+    2,
+    2 * (0x11a7 + 2),
+    (result, start) => {
+      for (let i = 0; i < 0x11a7 + 2; i++)
+        result.setUint16(start + 2 * i, 0xffff);
+      result.setUint16(start + 2 * (0x1100 + 0), 0x9);
+      result.setUint16(start + 2 * (0x1161 + 0), 0xa);
+      result.setUint16(start + 2 * (0x11a7 + 1), 0xb);
+    },
+  );
+  const uhdef = makeSparseWithDonkeyVote(2, 2 * 3, (result, start) => {
     result.setUint16(start + 2 * 0, 0xffff); // (null)
     result.setUint16(start + 2 * 1, 5); // f
     result.setUint16(start + 2 * 2, 0xffff); // (null)
   });
-  const uhman = empty;
+  const egroup = empty;
+  const esubgroup = empty;
+  const eversion = empty;
+  const estatus = empty;
+  const equal = empty;
   const seqb = empty;
   const seqp = empty;
   const seqn = empty;
+  const seqa = empty;
+  const seqe = empty;
+  const seqk = empty;
+  const pyk: string[] = [];
+  const pyi = empty;
+  const pyp = empty;
   return {
     info,
     string,
+    nametok,
     bits,
     ebits,
     pagebits,
     name,
+    nameseq,
     aliasc,
     aliasi,
     aliass,
     aliast,
+    varc,
+    vari,
+    varp,
+    vart,
     dnrp,
     gb,
     gc,
@@ -76,21 +105,86 @@ export function getData(): Data {
     age,
     hlvt,
     hjsn,
-    uhdef,
-    uhman,
+    unihan: { kDefinition: uhdef },
+    egroup,
+    esubgroup,
+    eversion,
+    estatus,
+    equal,
     seqb,
     seqp,
     seqn,
+    seqa,
+    seqe,
+    seqk,
+    pyk,
+    pyi,
+    pyp,
   };
 }
 
-function makeSparseWithDonkeyVote(
+// Replaces the sequences with the given points and aliases, where each alias
+// is a string index and an alias type.
+export function withSequences(
+  data: Data,
+  sequences: { points: number[]; aliases: [number, AliasType][] }[],
+): Data {
+  const count = sequences.length;
+  const points = sequences.flatMap((x) => x.points);
+  const aliases = sequences.flatMap((x) => x.aliases);
+  const seqp = new DataView(new ArrayBuffer(count * 5 + points.length * 4));
+  const seqa = new DataView(new ArrayBuffer(count * 5 + aliases.length * 3));
+  let pointStart = 0;
+  let aliasStart = 0;
+  sequences.forEach((x, i) => {
+    seqp.setUint32(i * 5 + 0, pointStart);
+    seqp.setUint8(i * 5 + 4, x.points.length);
+    seqa.setUint32(i * 5 + 0, aliasStart);
+    seqa.setUint8(i * 5 + 4, x.aliases.length);
+    pointStart += x.points.length;
+    aliasStart += x.aliases.length;
+  });
+  points.forEach((x, i) => seqp.setUint32(count * 5 + i * 4, x));
+  aliases.forEach(([index, type], i) => {
+    seqa.setUint16(count * 5 + i * 2, index);
+    seqa.setUint8(count * 5 + aliases.length * 2 + i, type);
+  });
+  return { ...data, info: { ...data.info, sequenceCount: count }, seqp, seqa };
+}
+
+function makeStringPool(strings: string[]): DataView {
+  const encoder = new TextEncoder();
+  const bytes = strings.map((x) => encoder.encode(x));
+  const base = 4 + (strings.length + 1) * 4;
+  const len = bytes.reduce((a, x) => a + x.length, 0);
+  const result = new DataView(new ArrayBuffer(base + len));
+  result.setUint32(0, strings.length);
+  let offset = 0;
+  bytes.forEach((x, i) => {
+    result.setUint32(4 + i * 4, offset);
+    new Uint8Array(result.buffer, base + offset).set(x);
+    offset += x.length;
+  });
+  result.setUint32(4 + strings.length * 4, offset);
+  return result;
+}
+
+// Two-stage trie with 256-point blocks, where block i is for page i,
+// covering the given number of pages (all of them by default, or fewer
+// for the chunk of a sparse file).
+export function makeSparseWithDonkeyVote(
+  width: number,
   len: number,
   fun: (_: DataView, start: number) => void,
+  pages = 0x1100,
 ): DataView {
-  const start = 0x1100 * 2;
+  const start = 8 + pages * 2;
   const result = new DataView(new ArrayBuffer(start + len));
-  for (let i = 0; i < 0x1100; i++) result.setUint16(i * 2, i);
+  result.setUint8(0, width);
+  result.setUint8(1, 2);
+  result.setUint8(3, 8);
+  result.setUint16(6, pages);
+  for (let i = 0; i < pages; i++) result.setUint16(8 + i * 2, i);
   fun(result, start);
   return result;
 }